@@ -3,7 +3,7 @@ use virus_alarm::prelude::*;
 
 fn set_up() -> Simulation {
 	let board = Board::default();
-	let report_plan = ReportPlan { num_simulations: 5, days: 10 };
+	let report_plan = ReportPlan { num_simulations: 5, days: 10, ..Default::default() };
 	Simulation::new(board, report_plan)
 }
 
@@ -1,22 +1,35 @@
 use virus_alarm::prelude::*;
+use virus_alarm::building::Neighbourhood;
 
 
 fn main() {
 	let simulation_builder = SimulationBuilder {
         board_builder: BoardBuilder{
                 healthy: 100,
+                exposed: 0,
                 infected1: 0,
                 infected2: 0,
                 infected3: 0,
+                asymptomatic: 0,
                 sick: 3,
                 immune: 20,
                 buildings: vec![(0, 0)],
+                graph_buildings: vec![(4, vec![(0, 1), (2, 3)])],
                 spreading: Spreading::OneNear,
+                transmission: None,
+                neighbourhood: Neighbourhood::Moore(1),
+                penalty: 0,
+                disease: Disease::default(),
+                testing: None,
+                hospital: None,
         },
         report_plan: ReportPlan{
                 num_simulations: 1,
                 days: 10,
-        }
+                seed: None,
+                stop: StopRule::Days,
+        },
+        vaccination: None,
     };
 
     let pretty = ron::ser::PrettyConfig::new()
@@ -64,10 +64,12 @@ impl Input {
                 immune: self.immune,
                 buildings: self.buildings(),
                 spreading: SPREADING,
+                ..Default::default()
             },
             report_plan: ReportPlan {
                 num_simulations,
                 days: 10,
+                ..Default::default()
             },
         }
         .build()
@@ -119,6 +119,7 @@ impl Component for Model {
                     ReportPlan {
                         num_simulations,
                         days: 10,
+                        ..Default::default()
                     },
                 )
                 .run();
@@ -1,7 +1,13 @@
-use crate::recording::CountingTable;
+use std::collections::HashMap;
+use crate::recording::{CountingTable, Flow, Infection, Stage};
+use crate::population::Tag;
+#[cfg(feature = "event-log")]
+use crate::event_log::{Event, EventLog};
 use core::fmt::Display;
-use crate::{BuildingBuilder, Building, Population, Individual, Recording, building::Spreading};
+use crate::errors::{ActionError, BoardError, BuildingError};
+use crate::{BuildingBuilder, Building, Population, Individual, Recording, building::{Spreading, Neighbourhood}, disease::Disease, probability::Probability, testing::Testing, hospital::Hospital, vaccination::{Vaccination, Eligibility}, policy::Action, observer::{Observer, Phase}};
 use getset::{Getters, Setters, MutGetters};
+use rand::SeedableRng;
 use serde::{Serialize, Deserialize};
 
 /// Builder for the `Board`.
@@ -13,12 +19,17 @@ use serde::{Serialize, Deserialize};
 /// specially useful as a configuration file.
 ///   
 /// A `Board` could be in the middle of a game, derefore (de)serialization 
-/// turns out to be less human-friendly.
+/// turns out to be less human-friendly, but it keeps the whole state of the game.
+/// See `Board` for saving and resuming a game.
 #[derive(Debug, Clone, PartialEq, Eq, Getters, Setters, MutGetters, Serialize, Deserialize, Default)]
 pub struct BoardBuilder {
 	/// Number of healthy individuals
     #[getset(get = "pub", set = "pub", get_mut = "pub")]
     pub healthy: usize,
+    /// Number of exposed individuals
+    #[getset(get = "pub", set = "pub", get_mut = "pub")]
+    #[serde(default)]
+    pub exposed: usize,
     /// Number of infected1 individuals
     #[getset(get = "pub", set = "pub", get_mut = "pub")]
     pub infected1: usize,
@@ -28,6 +39,10 @@ pub struct BoardBuilder {
     /// Number of infected3 individuals
     #[getset(get = "pub", set = "pub", get_mut = "pub")]
     pub infected3: usize,
+    /// Number of asymptomatic individuals
+    #[getset(get = "pub", set = "pub", get_mut = "pub")]
+    #[serde(default)]
+    pub asymptomatic: usize,
     /// Number of sick individuals
     #[getset(get = "pub", set = "pub", get_mut = "pub")]
     pub sick: usize,
@@ -37,49 +52,200 @@ pub struct BoardBuilder {
     /// Current state of the buildings in the game
     #[getset(get = "pub", set = "pub", get_mut = "pub")]
     pub buildings: Vec<(usize, usize)>,
+    /// Buildings whose seats are the nodes of a graph, given by the number of seats 
+    /// and the pairs of seats in contact, see `BuildingBuilder::with_graph`.
+    #[getset(get = "pub", set = "pub", get_mut = "pub")]
+    #[serde(default)]
+    pub graph_buildings: Vec<(usize, Vec<(usize, usize)>)>,
     /// Spreading mode
     #[getset(get = "pub", set = "pub", get_mut = "pub")]
     pub spreading: Spreading,
+    /// Probability that a contact between an infected and a healthy individual transmits the virus
+    ///
+    /// If `None`, contacts always transmit the virus.
+    #[getset(get = "pub", set = "pub", get_mut = "pub")]
+    #[serde(default)]
+    pub transmission: Option<Probability>,
+    /// Neighbourhood of seats in all buildings, used by the `OneNeighbour` spreading mode
+    #[getset(get = "pub", set = "pub", get_mut = "pub")]
+    #[serde(default)]
+    pub neighbourhood: Neighbourhood,
+    /// Penalty of closing any of the buildings for a day, see `Recording::daily_score`
+    #[getset(get = "pub", set = "pub", get_mut = "pub")]
+    #[serde(default)]
+    pub penalty: usize,
+    /// Disease model, which determines how individuals progress each day
+    #[getset(get = "pub", set = "pub", get_mut = "pub")]
+    #[serde(default)]
+    pub disease: Disease,
+    /// Testing of individuals, where positives are placed into quarantine
+    ///
+    /// If `None`, nobody is tested.
+    #[getset(get = "pub", set = "pub", get_mut = "pub")]
+    #[serde(default)]
+    pub testing: Option<Testing>,
+    /// Hospital beds for sick individuals
+    ///
+    /// If `None`, there is no limit of beds and nobody dies.
+    #[getset(get = "pub", set = "pub", get_mut = "pub")]
+    #[serde(default)]
+    pub hospital: Option<Hospital>,
 }
 
 impl BoardBuilder {
+	/// Returns the corresponding board.
+	///
+	/// # Panics
+	///
+	/// If the configuration is not valid, see `try_build`.
 	pub fn build(self) -> Board {
+		self.try_build().expect("building the board failed!")
+	}
+
+	/// Returns the corresponding board, after checking that the configuration is valid.
+	///
+	/// # Errors
+	///
+	/// If a pair of seats in contact, of a graph building or of a `Neighbourhood::Graph`, 
+	/// is not valid for a building, see `BuildingBuilder::try_build`.
+	/// The disease model is always valid, since it is checked whenever it changes, see `Disease::validate`.
+	///
+	/// # Examples
+	///
+	/// A contact with a fourth seat, in a building with three seats.
+	/// ```
+	/// # use virus_alarm::prelude::*;
+	/// # use virus_alarm::errors::{BoardError, BuildingError};
+	/// let board_builder = BoardBuilder {
+	///     healthy: 3,
+	///     graph_buildings: vec![(3, vec![(0, 1), (2, 3)])],
+	///     ..Default::default()
+	/// };
+	/// let source = BuildingError::ContactOutOfRange { seat: 3, capacity: 3 };
+	/// let error = BoardError::Building { building: 0, source };
+	/// assert_eq!(board_builder.try_build().err(), Some(error));
+	/// ```
+	pub fn try_build(self) -> Result<Board, BoardError> {
 		// Population
 		let mut population_vec = vec![Individual::Healthy; self.healthy];
+		population_vec.append(&mut vec![Individual::Exposed; self.exposed]);
 		population_vec.append(&mut vec![Individual::Infected1; self.infected1]);
 		population_vec.append(&mut vec![Individual::Infected2; self.infected2]);
 		population_vec.append(&mut vec![Individual::Infected3; self.infected3]);
+		population_vec.append(&mut vec![Individual::Asymptomatic; self.asymptomatic]);
 		population_vec.append(&mut vec![Individual::Sick; self.sick]);
 		population_vec.append(&mut vec![Individual::Immune; self.immune]);
 		let population = Population::from(population_vec);
 
 		// Buildings
-		let buildings = self.buildings.iter().map(|&(cols, rows)| 
+		let mut buildings: Vec<BuildingBuilder> = self.buildings.iter().map(|&(cols, rows)| 
 			BuildingBuilder::new("Defult")
 				.with_size(cols, rows)
 				.with_spreading(self.spreading)
+				.with_transmission(self.transmission.unwrap_or(Probability::ONE))
+				.with_neighbourhood(self.neighbourhood.clone())
+				.with_penalty(self.penalty)
 				.and_is_open()
-				.build()
 			).collect();
+		buildings.extend(self.graph_buildings.iter().map(|(seats, contacts)| 
+			BuildingBuilder::new("Defult")
+				.with_graph(*seats, contacts.clone())
+				.with_spreading(self.spreading)
+				.with_transmission(self.transmission.unwrap_or(Probability::ONE))
+				.with_penalty(self.penalty)
+				.and_is_open()
+			));
+		let buildings = buildings.into_iter()
+			.enumerate()
+			.map(|(building, builder)| builder.try_build().map_err(|source| BoardError::Building { building, source }))
+			.collect::<Result<Vec<Building>, BoardError>>()?;
 
-		Board::new(population, buildings)
+		let mut board = Board::try_new(population, buildings)?;
+		board.set_disease(self.disease);
+		board.set_testing(self.testing);
+		board.set_hospital(self.hospital);
+		Ok(board)
 	}
 }
 
 
 /// Represents the state of the game and have high level commands.
-#[derive(Debug, Clone, PartialEq, Eq, Getters, MutGetters)]
+///
+/// # Snapshots
+///
+/// A board can be serialized at any point of the game, even in the middle of a stage,
+/// and deserialized to resume the game, or cloned to branch many futures from one state.
+/// The snapshot includes the individuals with their identifiers, the ones at home or in quarantine,
+/// the position of the population iterator and the recording of the game so far.
+/// The source of randomness is not part of the board, so resuming with the same seed 
+/// gives the same future.
+///
+/// # Examples
+///
+/// Pause a game and resume it later.
+/// ```
+/// # use virus_alarm::prelude::*;
+/// let mut board = Board::default();
+/// board.advance_many(2);
+/// let snapshot = ron::to_string(&board).unwrap();
+/// let mut resumed: Board = ron::from_str(&snapshot).unwrap();
+/// assert_eq!(resumed, board);
+/// resumed.advance();
+/// assert_eq!(resumed.counting_table().days(), 4);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Getters, MutGetters, Serialize, Deserialize)]
 pub struct Board {
-	/// Current population in the game
-    #[getset(get = "pub", get_mut)]
+	/// Current population in the game, without the individuals in quarantine
+    #[getset(get = "pub", get_mut = "pub(crate)")]
     population: Population,
     /// Current state of the buildings in the game
-    #[getset(get = "pub")]
+    #[getset(get = "pub", get_mut = "pub(crate)")]
     buildings: Vec<Building>,
-    inactive: Vec<Individual>, 
+    /// Individuals that stay at home, with their tags
+    inactive: Vec<(Individual, Tag)>, 
+    /// Disease model, which determines how individuals progress each day
+    #[getset(get = "pub")]
+    disease: Disease,
+    /// Flows of the current stage, see `Flow`
+    #[getset(get = "pub")]
+    flows: HashMap<Flow, usize>,
+    /// Infections of the current stage, see `Infection`
+    #[getset(get = "pub")]
+    infections: Vec<Infection>,
+    /// Number of visitors and of infected visitors of each building in the current stage
+    #[getset(get = "pub")]
+    visitors: Vec<(usize, usize)>,
+    /// Testing of individuals, see `test`
+    #[getset(get = "pub")]
+    testing: Option<Testing>,
+    /// Individuals in quarantine, with their tags 
+    /// and the days they still have to stay in quarantine
+    quarantine: Vec<(Individual, Tag, usize)>,
+    /// Number of tests used in the current stage
+    #[getset(get = "pub")]
+    tests: usize,
+    /// Hospital beds for sick individuals, see `propagate`
+    #[getset(get = "pub")]
+    hospital: Option<Hospital>,
+    /// Number of sick individuals without a hospital bed in the current stage
+    #[getset(get = "pub")]
+    overload: usize,
+    /// Vaccination campaign, see `vaccinate`
+    #[getset(get = "pub")]
+    vaccination: Option<Vaccination>,
+    /// Number of vaccine doses given since the last registered stage, 
+    /// by the vaccination campaign or by `immunize`
+    #[getset(get = "pub")]
+    doses: usize,
+    /// Number of stages advanced
+    #[getset(get = "pub")]
+    day: usize,
     /// Recording device
     #[getset(get = "pub", get_mut)]
     recording: Recording,
+    /// Log of what happened to each individual, see `event_log`
+    #[cfg(feature = "event-log")]
+    event_log: EventLog,
 }
 
 impl Board {
@@ -89,22 +255,46 @@ impl Board {
 	///
 	/// If not all buildings have the same spreading mode.
 	pub fn new(population: Population, buildings: Vec<Building>) -> Self {
-		assert_eq!(
-			buildings.iter().map(|b| b.spreading()).min(), 
-			buildings.iter().map(|b| b.spreading()).max()
-		);
+		Board::try_new(population, buildings).expect("creating the board failed!")
+	}
+
+	/// Creates a new board with the specified population and buildings as default.
+	///
+	/// # Errors
+	///
+	/// If not all buildings have the same spreading mode.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// # use virus_alarm::prelude::*;
+	/// # use virus_alarm::{Building, errors::BoardError};
+	/// let mut bakery = Building::new(2, 1, "Bakery");
+	/// bakery.set_spreading(Spreading::Everyone);
+	/// let school = Building::new(2, 1, "School");
+	/// let board = Board::try_new(Population::default(), vec![bakery, school]);
+	/// assert_eq!(board.err(), Some(BoardError::MixedSpreading));
+	/// ```
+	pub fn try_new(population: Population, buildings: Vec<Building>) -> Result<Self, BoardError> {
+		if buildings.iter().map(|b| b.spreading()).min() != buildings.iter().map(|b| b.spreading()).max() {
+			return Err(BoardError::MixedSpreading);
+		}
 		let default = Board::default();
 		let recording = Recording::new(population.clone(), buildings.clone());
-		Board {
+		Ok(Board {
 			population,
 			buildings,
 			recording,
 			..default
-		}
+		})
 	}
 
 	/// Immunize one person in the population. 
 	/// 
+	/// Before the first stage, the initial day of the recording is changed. 
+	/// Afterwards, recorded days are kept as they are: the immunization counts as a dose, 
+	/// see `doses`, and is recorded with the next stage.
+	///
 	/// # Errors
 	///
 	/// If there is no healthy individual to immunize.
@@ -117,15 +307,24 @@ impl Board {
 	/// let mut board = Board::default();
 	/// board.immunize();
 	/// assert_eq!(board.population().counting(Individual::Immune), 1);
+	/// assert_eq!(board.recording().counting_table().last_day()[&Individual::Immune], 1);
 	/// ```
 	pub fn immunize(&mut self) -> Result<&mut Self, crate::errors::ActionError> {
 		self.population_mut().immunize()?;
-		self.recording_mut().immunize()?;
+		if self.recording().timeline() == &0 {
+			self.recording_mut().immunize()?;
+		} else {
+			self.doses += 1;
+		}
 		Ok(self)
 	}
 
 	/// Reverse one individual from immune to healthy in the population. 
 	/// 
+	/// Before the first stage, the initial day of the recording is changed. 
+	/// Afterwards, recorded days are kept as they are and one of the doses given since 
+	/// the last stage, if any, is taken back.
+	///
 	/// # Errors
 	///
 	/// If there is no immune individual to reverse.
@@ -143,7 +342,45 @@ impl Board {
 	/// ```
 	pub fn reverse_immunize(&mut self) -> Result<&mut Self, crate::errors::ActionError> {
 		self.population_mut().reverse_immunize()?;
-		self.recording_mut().reverse_immunize()?;
+		if self.recording().timeline() == &0 {
+			self.recording_mut().reverse_immunize()?;
+		} else {
+			self.doses = self.doses.saturating_sub(1);
+		}
+		Ok(self)
+	}
+
+	/// Takes an action on the board, as decided by a `Policy`.
+	///
+	/// # Errors
+	///
+	/// - If there are not enough healthy individuals to immunize. 
+	///   In that case, all healthy individuals are immunized.
+	/// - If there is no building with the name to close or open, 
+	///   or it can not be closed, see `try_close` and `try_open`.
+	///
+	/// # Examples
+	///
+	/// Closing the school.
+	/// ```
+	/// # use virus_alarm::prelude::*;
+	/// # use virus_alarm::policy::Action;
+	/// let mut board = Board::default();
+	/// board.apply(Action::Close("School".to_string())).unwrap();
+	/// assert!(!board.buildings()[2].is_open());
+	/// ```
+	pub fn apply(&mut self, action: Action) -> Result<&mut Self, crate::errors::ActionError> {
+		match action {
+			Action::Close(name) => { self.try_close(name)?; },
+			Action::Open(name) => { self.try_open(name)?; },
+			Action::Immunize(n) => {
+				for _ in 0..n {
+					self.immunize()?;
+				}
+			},
+			Action::SetSpreading(spreading) => { self.set_spreading(spreading); },
+			Action::SetVaccination(vaccination) => { self.set_vaccination(vaccination); },
+		}
 		Ok(self)
 	}
 
@@ -152,9 +389,31 @@ impl Board {
 	/// # Remarks
 	///
 	/// This is equivalent to use `advance` many times.
-	pub fn advance_many(&mut self, num_stages: usize) -> &mut Self{
+	pub fn advance_many(&mut self, num_stages: usize) -> &mut Self {
+		self.advance_many_with_rng(num_stages, &mut rand::thread_rng())
+	}
+
+	/// Advance the specified number of stages in the game, drawing randomness from `rng`.
+	///
+	/// # Remarks
+	///
+	/// This is equivalent to use `advance_with_rng` many times.
+	///
+	/// # Examples
+	///
+	/// The same seed always leads to the same game.
+	/// ```
+	/// # use virus_alarm::prelude::*;
+	/// # use rand::SeedableRng;
+	/// let mut board = Board::default();
+	/// board.advance_many_with_rng(5, &mut rand_pcg::Pcg64::seed_from_u64(1));
+	/// let mut other_board = Board::default();
+	/// other_board.advance_many_with_rng(5, &mut rand_pcg::Pcg64::seed_from_u64(1));
+	/// assert_eq!(board.counting_table(), other_board.counting_table());
+	/// ```
+	pub fn advance_many_with_rng<R: ?Sized + rand::Rng>(&mut self, num_stages: usize, rng: &mut R) -> &mut Self {
 		for _ in 0..num_stages {
-			self.advance();
+			self.advance_with_rng(rng);
 		}
 		self
 	}
@@ -163,11 +422,55 @@ impl Board {
 	///
 	/// Returns the number of newly infected individuals
 	pub fn advance_population(&mut self) -> usize {
-		self.visit();
-		self.propagate();
-		self.go_home()
+		self.advance_population_with_rng(&mut rand::thread_rng())
+	}
+
+	/// Advance the population a stage in the game, without registering the changes, 
+	/// drawing randomness from `rng`.
+	///
+	/// Returns the number of newly infected individuals
+	pub fn advance_population_with_rng<R: ?Sized + rand::Rng>(&mut self, rng: &mut R) -> usize {
+		self.advance_population_observed_with_rng(&mut no_observer, rng)
+	}
+
+	/// Advance the population a stage in the game, without registering the changes, 
+	/// calling `observer` after each step and drawing randomness from `rng`.
+	///
+	/// Returns the number of newly infected individuals
+	pub fn advance_population_observed_with_rng<O, R>(&mut self, observer: &mut O, rng: &mut R) -> usize 
+	where
+		O: ?Sized + Observer,
+		R: ?Sized + rand::Rng,
+	{
+		self.day += 1;
+		self.vaccinate_with_rng(rng);
+		self.test_with_rng(rng);
+		self.visit_with_rng(rng);
+		observer.observe(Phase::Visit, self);
+		self.propagate_with_rng(rng);
+		observer.observe(Phase::Propagate, self);
+		let newly_infected = self.go_home();
+		observer.observe(Phase::GoHome, self);
+		newly_infected
 	}
 
+	/// Advance the population a stage in the game, without shuffling nor registering the changes.
+	///
+	/// Individuals visit the buildings in the order of the population. 
+	/// Returns the number of newly infected individuals
+	///
+	/// The result is deterministic: randomness is drawn from a fixed seed, 
+	/// and contacts always transmit the virus, see `MarkovChain::try_from`.
+	pub(crate) fn advance_population_in_order(&mut self) -> usize {
+		debug_assert!(
+			self.buildings.iter().all(|building| building.transmission().is_certain()),
+			"advancing in order needs contacts that always transmit the virus"
+		);
+		self.day += 1;
+		self.visit_in_order();
+		self.propagate_with_rng(&mut rand_pcg::Pcg64::seed_from_u64(0));
+		self.go_home()
+	}
 
 	/// Advance a stage in the game.
 	///
@@ -175,37 +478,208 @@ impl Board {
 	///
 	/// This is a short method for all steps involved in a stage.
 	pub fn advance(&mut self) -> &mut Self {
-		let newly_infected = self.advance_population();
-		self.recording.register(newly_infected, &self.buildings);
+		self.advance_with_rng(&mut rand::thread_rng())
+	}
+
+	/// Advance a stage in the game, drawing randomness from `rng`.
+	///
+	/// # Remarks
+	///
+	/// This is a short method for all steps involved in a stage.
+	pub fn advance_with_rng<R: ?Sized + rand::Rng>(&mut self, rng: &mut R) -> &mut Self {
+		self.advance_observed_with_rng(&mut no_observer, rng)
+	}
+
+	/// Advance a stage in the game, calling `observer` after visiting, propagating and going home.
+	///
+	/// See `Observer` for more.
+	pub fn advance_observed<O: ?Sized + Observer>(&mut self, observer: &mut O) -> &mut Self {
+		self.advance_observed_with_rng(observer, &mut rand::thread_rng())
+	}
+
+	/// Advance a stage in the game, calling `observer` after visiting, propagating and going home, 
+	/// and drawing randomness from `rng`.
+	///
+	/// The observer is called before the stage is registered in the recording.
+	pub fn advance_observed_with_rng<O, R>(&mut self, observer: &mut O, rng: &mut R) -> &mut Self 
+	where
+		O: ?Sized + Observer,
+		R: ?Sized + rand::Rng,
+	{
+		self.advance_population_observed_with_rng(observer, rng);
+		let stage = Stage {
+			counting: self.counting_all(),
+			quarantined: self.quarantined(),
+			overload: self.overload,
+			flows: &self.flows,
+			infections: &self.infections,
+			visitors: &self.visitors,
+			tests: self.tests,
+			doses: self.doses,
+			buildings: &self.buildings,
+		};
+		self.recording.register(stage);
+		self.doses = 0;
 		self
 	}
 
-	/// First step of any stage
+	/// Step before testing, where the doses of the vaccination campaign of the day are given.
 	///
-	/// In this step, buildings are populated by non-sick individuals randomly.
+	/// See `Vaccination` for more.
+	pub fn vaccinate(&mut self) -> &mut Self {
+		self.vaccinate_with_rng(&mut rand::thread_rng())
+	}
+
+	/// Step before testing, where the doses of the vaccination campaign of the day are given, 
+	/// drawing randomness from `rng`.
 	///
-	/// # Errors
+	/// Doses are given to random eligible individuals, who become immune if they were healthy, see `Eligibility`. 
+	/// The current day is `day`, which is updated when advancing the population.
+	///
+	/// # Examples
 	///
-	/// If visiting any of the building fails.
+	/// Vaccinating the default population on the first day.
+	/// ```
+	/// # use virus_alarm::prelude::*;
+	/// # use virus_alarm::vaccination::Vaccination;
+	/// let mut board = Board::default();
+	/// board.set_vaccination(Some(Vaccination { doses_per_day: 10, ..Default::default() }));
+	/// board.vaccinate();
+	/// assert_eq!(board.population().counting(Individual::Immune), 10);
+	/// assert_eq!(board.doses(), &10);
+	/// ```
+	pub fn vaccinate_with_rng<R: ?Sized + rand::Rng>(&mut self, rng: &mut R) -> &mut Self {
+		let vaccination = match &self.vaccination {
+			Some(vaccination) => vaccination.clone(),
+			None => return self,
+		};
+		let doses = vaccination.doses(self.day);
+		if doses == 0 {
+			return self;
+		}
+		// Randomness
+		self.population.shuffle(rng);
+		let mut given = 0;
+		let mut vaccinated = Vec::new();
+		for (i, tag) in self.population.remaining_with_tags() {
+			let eligible = match vaccination.eligibility {
+				Eligibility::Healthy => i == Individual::Healthy && !tag.infected,
+				Eligibility::Everyone => !i.stays_home(),
+			};
+			if given < doses && eligible {
+				given += 1;
+				if i == Individual::Healthy {
+					vaccinated.push((Individual::Immune, Tag { days: 0, ..tag }));
+					continue;
+				}
+			}
+			vaccinated.push((i, tag));
+		}
+		self.population = Population::from(vaccinated);
+		self.doses += given;
+		self
+	}
+
+	/// Step before visiting, where individuals are tested and positives are placed into quarantine.
+	///
+	/// See `Testing` for more.
+	pub fn test(&mut self) -> &mut Self {
+		self.test_with_rng(&mut rand::thread_rng())
+	}
+
+	/// Step before visiting, where individuals are tested and positives are placed into quarantine, 
+	/// drawing randomness from `rng`.
+	///
+	/// Up to the testing capacity, random individuals that are not sick nor dead are tested. 
+	/// Individuals in quarantine are not part of the population until they are released
+	/// when going home, see `go_home`. If `Testing::quarantine_days` is zero, 
+	/// positives are counted as tests but stay in the population.
+	///
+	/// # Examples
+	///
+	/// Perfect tests find the two infected individuals of the default population.
+	/// ```
+	/// # use virus_alarm::prelude::*;
+	/// # use virus_alarm::testing::Testing;
+	/// let mut board = Board::default();
+	/// board.set_testing(Some(Testing { capacity: 100, quarantine_days: 5, ..Default::default() }));
+	/// board.test();
+	/// assert_eq!(board.quarantined(), 2);
+	/// assert_eq!(board.population().len(), 98);
+	/// assert_eq!(board.tests(), &100);
+	/// ```
+	pub fn test_with_rng<R: ?Sized + rand::Rng>(&mut self, rng: &mut R) -> &mut Self {
+		self.tests = 0;
+		let testing = match &self.testing {
+			Some(testing) => testing.clone(),
+			None => return self,
+		};
+		// Randomness
+		self.population.shuffle(rng);
+		let mut remaining = Vec::new();
+		for (i, tag) in self.population.remaining_with_tags() {
+			if self.tests < testing.capacity && !i.stays_home() {
+				self.tests += 1;
+				if testing.is_positive(&i, rng) && testing.quarantine_days > 0 {
+					self.quarantine.push((i, tag, testing.quarantine_days));
+					continue;
+				}
+			}
+			remaining.push((i, tag));
+		}
+		self.population = Population::from(remaining);
+		self
+	}
+
+	/// First step of any stage
+	///
+	/// In this step, buildings are populated by non-sick individuals randomly.
 	pub fn visit(&mut self) -> &mut Self {
+		self.visit_with_rng(&mut rand::thread_rng())
+	}
+
+	/// First step of any stage, drawing randomness from `rng`.
+	///
+	/// In this step, buildings are populated by non-sick individuals randomly.
+	pub fn visit_with_rng<R: ?Sized + rand::Rng>(&mut self, rng: &mut R) -> &mut Self {
 		// Randomness
-		self.population.shuffle(&mut rand::thread_rng());
+		self.population.shuffle(rng);
+		self.visit_in_order()
+	}
+
+	/// First step of any stage, where individuals visit the buildings in the order of the population.
+	fn visit_in_order(&mut self) -> &mut Self {
+		self.flows.clear();
 		// Visiting
 		for index in 0..self.buildings.len() {
 			self.visit_building(index);
 		}
 		// Remaining individuals are stored in inactive 
-		self.inactive.extend(self.population.clone()); 
+		let remaining = self.population.remaining_with_tags();
+		#[cfg(feature = "event-log")]
+		for &(_, tag) in &remaining {
+			self.event_log.push(Event::StayHome { day: self.day, id: tag.id });
+		}
+		self.inactive.extend(remaining); 
 		self
 	}
 
 	fn visit_building(&mut self, index: usize) -> &Building {
 		while !self.buildings[index].is_full() & self.buildings[index].is_open() {
-			match self.population.next() {
-				Some(i) => {
+			match self.population.next_with_tag() {
+				Some((i, tag)) => {
 					match i {
-						Individual::Sick => self.inactive.push(i),
-						i => self.buildings[index].try_push(i).expect("pushing on a building with space failed!"),
+						i if i.stays_home() => {
+							#[cfg(feature = "event-log")]
+							self.event_log.push(Event::StayHome { day: self.day, id: tag.id });
+							self.inactive.push((i, tag))
+						},
+						i => {
+							#[cfg_attr(not(feature = "event-log"), allow(unused_variables))]
+							let seat = self.buildings[index].try_push_with_tag(i, tag).expect("pushing on a building with space failed!");
+							#[cfg(feature = "event-log")]
+							self.event_log.push(Event::Visit { day: self.day, id: tag.id, building: index, seat });
+						},
 					}
 				},
 				None => break,
@@ -218,17 +692,93 @@ impl Board {
 	///
 	/// In this step, virus is propagated in each building.
 	pub fn propagate(&mut self) {
+		self.propagate_with_rng(&mut rand::thread_rng())
+	}
+
+	/// Second step of any stage, drawing randomness from `rng`.
+	///
+	/// In this step, sick individuals go to the hospital, if any, and virus is propagated in each building.
+	/// Who infected whom is kept in `infections`.
+	pub fn propagate_with_rng<R: ?Sized + rand::Rng>(&mut self, rng: &mut R) {
+		#[cfg(feature = "event-log")]
+		let before = self.identified();
+		self.hospitalize_with_rng(rng);
+		// Flows other than infections
+		let everyone = self.buildings.iter()
+			.flat_map(|building| building.people_with_tags())
+			.chain(self.inactive.iter().copied())
+			.chain(self.quarantine.iter().map(|&(i, tag, _)| (i, tag)));
+		for (i, tag) in everyone {
+			if let Some(flow) = self.disease.flow(i, tag.days) {
+				*self.flows.entry(flow).or_insert(0) += 1;
+			}
+		}
+		// Visitors
+		self.visitors = self.buildings.iter()
+			.map(|building| {
+				let visitors = building.people_with_tags().count();
+				let infected = building.people_with_tags().filter(|(i, _)| i.is_infected()).count();
+				(visitors, infected)
+			})
+			.collect();
 		// Buildings
-		for building in self.buildings.iter_mut() {
-			building.propagate();
+		self.infections.clear();
+		for (index, building) in self.buildings.iter_mut().enumerate() {
+			let day = self.day;
+			let infections = building.propagate_tracing_with_rng(&self.disease, rng)
+				.into_iter()
+				.map(|(infector, infectee, seat)| Infection { infector, infectee, building: index, seat, day });
+			self.infections.extend(infections);
 		}
 		// Inactive
-		for i in self.inactive.iter_mut() {
-			*i = match i {
-				Individual::Infected1 => Individual::Infected2,
-				Individual::Infected2 => Individual::Infected3,
-				Individual::Infected3 => Individual::Sick,
-				_ => *i,
+		for (i, tag) in self.inactive.iter_mut() {
+			let (next_individual, next_days) = self.disease.step(*i, tag.days, false, rng);
+			*i = next_individual;
+			tag.days = next_days;
+		}
+		// Quarantine
+		for (i, tag, _) in self.quarantine.iter_mut() {
+			let (next_individual, next_days) = self.disease.step(*i, tag.days, false, rng);
+			*i = next_individual;
+			tag.days = next_days;
+		}
+		#[cfg(feature = "event-log")]
+		for ((id, before), (_, after)) in before.into_iter().zip(self.identified()) {
+			self.event_log.push(Event::Step { day: self.day, id, before, after });
+		}
+	}
+
+	/// Returns the identifiers and states of the individuals in buildings, at home and in quarantine, in this order.
+	#[cfg(feature = "event-log")]
+	fn identified(&self) -> Vec<(usize, Individual)> {
+		self.buildings.iter()
+			.flat_map(|building| building.people_with_tags())
+			.chain(self.inactive.iter().copied())
+			.chain(self.quarantine.iter().map(|&(i, tag, _)| (i, tag)))
+			.map(|(i, tag)| (tag.id, i))
+			.collect()
+	}
+
+	/// Sick individuals take the hospital beds, and each one without a bed dies 
+	/// with the mortality of the hospital, drawing randomness from `rng`.
+	///
+	/// No randomness is drawn if there is no hospital.
+	fn hospitalize_with_rng<R: ?Sized + rand::Rng>(&mut self, rng: &mut R) {
+		self.overload = 0;
+		let hospital = match &self.hospital {
+			Some(hospital) => hospital.clone(),
+			None => return,
+		};
+		let sick: Vec<(&mut Individual, &mut Tag)> = self.inactive.iter_mut()
+			.map(|(i, tag)| (i, tag))
+			.chain(self.quarantine.iter_mut().map(|(i, tag, _)| (i, tag)))
+			.filter(|(i, _)| **i == Individual::Sick)
+			.collect();
+		self.overload = hospital.overload(sick.len());
+		for (i, tag) in sick.into_iter().skip(hospital.beds) {
+			if hospital.mortality.sample(rng) {
+				*i = Individual::Dead;
+				tag.days = 0;
 			}
 		}
 	}
@@ -242,11 +792,18 @@ impl Board {
 		// Collect 
 		// From buildings
 		for building in self.buildings.iter_mut() {
-			new_vec.append(&mut building.empty())
+			new_vec.append(&mut building.empty_with_tags())
 		}
-		let newly_infected: usize = new_vec.iter().filter(|&&i| i == Individual::Infected1).count();
+		let newly_infected: usize = new_vec.iter().filter(|&&(i, tag)| self.disease.is_newly_infected(i, tag.days)).count();
+		*self.flows.entry(Flow::Infection).or_insert(0) += newly_infected;
 		// From inactive
 		new_vec.append(&mut self.inactive);
+		// From quarantine
+		for (_, _, days_left) in self.quarantine.iter_mut() {
+			*days_left = days_left.saturating_sub(1);
+		}
+		new_vec.extend(self.quarantine.iter().filter(|&&(_, _, days_left)| days_left == 0).map(|&(i, tag, _)| (i, tag)));
+		self.quarantine.retain(|&(_, _, days_left)| days_left > 0);
 		let new_population = Population::from(new_vec);
 
 		// Update
@@ -256,7 +813,13 @@ impl Board {
 	}
 
 
-	/// Closes a building
+	/// Toggles (changes the open state) the buildings with the given name.
+	///
+	/// If there is no building with the given name, nothing changes: see `try_toggle` to detect it.
+	///
+	/// # Panics
+	///
+	/// If one of the buildings is open and not empty.
 	pub fn toggle<S: Display>(&mut self, name: S) -> &mut Self {
 		for building in self.buildings.iter_mut() {
 			if building.name() == name.to_string() {
@@ -266,7 +829,30 @@ impl Board {
 		self
 	}
 
-	/// Closes a building
+	/// Toggles (changes the open state) the buildings with the given name.
+	///
+	/// # Errors
+	///
+	/// If there is no building with the given name, or if one of them is open and not empty.
+	/// In that case, nothing changes.
+	pub fn try_toggle<S: Display>(&mut self, name: S) -> Result<&mut Self, ActionError> {
+		let indexes = self.indexes_of(name)?;
+		if indexes.iter().any(|&index| self.buildings[index].is_open() && !self.buildings[index].is_empty()) {
+			return Err(BuildingError::Occupied.into());
+		}
+		for index in indexes {
+			self.buildings[index].try_toggle()?;
+		}
+		Ok(self)
+	}
+
+	/// Closes the buildings with the given name.
+	///
+	/// If there is no building with the given name, nothing changes: see `try_close` to detect it.
+	///
+	/// # Panics
+	///
+	/// If one of the buildings is not empty.
 	pub fn close<S: Display>(&mut self, name: S) -> &mut Self {
 		for building in self.buildings.iter_mut() {
 			if building.name() == name.to_string() {
@@ -276,7 +862,37 @@ impl Board {
 		self
 	}
 
-	/// Opens a building
+	/// Closes the buildings with the given name.
+	///
+	/// # Errors
+	///
+	/// If there is no building with the given name, or if one of them is not empty.
+	/// In that case, nothing changes.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// # use virus_alarm::prelude::*;
+	/// # use virus_alarm::errors::ActionError;
+	/// let mut board = Board::default();
+	/// let error = ActionError::UnknownBuilding("Zoo".to_string());
+	/// assert_eq!(board.try_close("Zoo").err(), Some(error));
+	/// assert!(board.try_close("School").is_ok());
+	/// ```
+	pub fn try_close<S: Display>(&mut self, name: S) -> Result<&mut Self, ActionError> {
+		let indexes = self.indexes_of(name)?;
+		if indexes.iter().any(|&index| !self.buildings[index].is_empty()) {
+			return Err(BuildingError::Occupied.into());
+		}
+		for index in indexes {
+			self.buildings[index].try_close()?;
+		}
+		Ok(self)
+	}
+
+	/// Opens the buildings with the given name.
+	///
+	/// If there is no building with the given name, nothing changes: see `try_open` to detect it.
 	pub fn open<S: Display>(&mut self, name: S) -> &mut Self {
 		for building in self.buildings.iter_mut() {
 			if building.name() == name.to_string() {
@@ -286,6 +902,81 @@ impl Board {
 		self
 	}
 
+	/// Opens the buildings with the given name.
+	///
+	/// # Errors
+	///
+	/// If there is no building with the given name.
+	pub fn try_open<S: Display>(&mut self, name: S) -> Result<&mut Self, ActionError> {
+		for index in self.indexes_of(name)? {
+			self.buildings[index].open();
+		}
+		Ok(self)
+	}
+
+	/// Returns the indexes of the buildings with the given name.
+	///
+	/// # Errors
+	///
+	/// If there is no building with the given name.
+	fn indexes_of<S: Display>(&self, name: S) -> Result<Vec<usize>, ActionError> {
+		let name = name.to_string();
+		let indexes: Vec<usize> = self.buildings.iter()
+			.enumerate()
+			.filter(|(_, building)| building.name() == name)
+			.map(|(index, _)| index)
+			.collect();
+		if indexes.is_empty() {
+			Err(ActionError::UnknownBuilding(name))
+		} else {
+			Ok(indexes)
+		}
+	}
+
+	/// Changes the penalty of closing the buildings with the given name for a day.
+	///
+	/// If there is no building with the given name, nothing changes: see `try_set_penalty` to detect it.
+	///
+	/// # Examples
+	///
+	/// Closing the school costs ten points per day.
+	/// ```
+	/// # use virus_alarm::prelude::*;
+	/// let mut board = Board::default();
+	/// board.set_penalty("School", 10).close("School").advance();
+	/// assert_eq!(board.recording().penalty()[2].1, vec![0, 10]);
+	/// ```
+	pub fn set_penalty<S: Display>(&mut self, name: S, new_penalty: usize) -> &mut Self {
+		for building in self.buildings.iter_mut() {
+			if building.name() == name.to_string() {
+				building.set_penalty(new_penalty);
+			}
+		}
+		self.recording_mut().set_penalty(&name.to_string(), new_penalty);
+		self
+	}
+
+	/// Changes the penalty of closing the buildings with the given name for a day.
+	///
+	/// # Errors
+	///
+	/// If there is no building with the given name.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// # use virus_alarm::prelude::*;
+	/// # use virus_alarm::errors::ActionError;
+	/// let mut board = Board::default();
+	/// let error = ActionError::UnknownBuilding("Zoo".to_string());
+	/// assert_eq!(board.try_set_penalty("Zoo", 10).err(), Some(error));
+	/// assert!(board.try_set_penalty("School", 10).is_ok());
+	/// ```
+	pub fn try_set_penalty<S: Display>(&mut self, name: S, new_penalty: usize) -> Result<&mut Self, ActionError> {
+		self.indexes_of(&name)?;
+		Ok(self.set_penalty(name, new_penalty))
+	}
+
 	/// Returns the spreading mode. 
 	///
 	/// See `Spreading` for more. 
@@ -317,12 +1008,125 @@ impl Board {
 		self
 	}
 
+	/// Changes the probability that a contact between an infected and 
+	/// a healthy individual transmits the virus, in all buildings.
+	pub fn set_transmission(&mut self, new_transmission: Probability) -> &mut Self {
+		for building in self.buildings.iter_mut() {
+			building.set_transmission(new_transmission);
+		}
+		self
+	}
+
+	/// Changes the neighbourhood of seats in all buildings, used by the `OneNeighbour` spreading mode.
+	///
+	/// Buildings whose seats are the nodes of a graph keep their contacts.
+	///
+	/// # Panics
+	///
+	/// If the neighbourhood is not valid for a building, see `Building::try_set_neighbourhood`.
+	///
+	/// # Examples
+	///
+	/// Theatre rows, where only people in the same row are in contact.
+	/// ```
+	/// # use virus_alarm::prelude::*;
+	/// # use virus_alarm::building::Neighbourhood;
+	/// let mut board = Board::default();
+	/// board.set_spreading(Spreading::OneNeighbour).set_neighbourhood(Neighbourhood::Row(1));
+	/// assert_eq!(board.buildings()[0].neighbourhood(), &Neighbourhood::Row(1));
+	/// ```
+	pub fn set_neighbourhood(&mut self, new_neighbourhood: Neighbourhood) -> &mut Self {
+		for building in self.buildings.iter_mut() {
+			if let Neighbourhood::Graph(_) = building.neighbourhood() {
+				continue;
+			}
+			building.set_neighbourhood(new_neighbourhood.clone());
+		}
+		self
+	}
+
+	/// Changes the disease model, which determines how individuals progress each day.
+	///
+	/// # Examples
+	///
+	/// A disease with two days of incubation.
+	/// ```
+	/// # use virus_alarm::prelude::*;
+	/// # use virus_alarm::disease::Recovery;
+	/// let mut board = Board::default();
+	/// board.set_disease(Disease::new(2, 3, None, Recovery::Immune));
+	/// assert_eq!(board.disease().incubation_days(), 2);
+	/// ```
+	pub fn set_disease(&mut self, new_disease: Disease) -> &mut Self {
+		self.disease = new_disease;
+		self
+	}
+
+	/// Changes the testing of individuals, see `test`.
+	///
+	/// Individuals already in quarantine stay there for the days they were assigned.
+	pub fn set_testing(&mut self, new_testing: Option<Testing>) -> &mut Self {
+		self.testing = new_testing;
+		self
+	}
+
+	/// Changes the hospital beds for sick individuals, see `Hospital`.
+	pub fn set_hospital(&mut self, new_hospital: Option<Hospital>) -> &mut Self {
+		self.hospital = new_hospital;
+		self
+	}
+
+	/// Changes the vaccination campaign, see `vaccinate`.
+	pub fn set_vaccination(&mut self, new_vaccination: Option<Vaccination>) -> &mut Self {
+		self.vaccination = new_vaccination;
+		self
+	}
+
+	/// Returns the number of individuals in quarantine.
+	pub fn quarantined(&self) -> usize {
+		self.quarantine.len()
+	}
+
+	/// Returns the number of individuals of each type, including the ones in quarantine.
+	///
+	/// # Examples
+	///
+	/// Default board.
+	/// ```
+	/// # use virus_alarm::prelude::*;
+	/// let board = Board::default();
+	/// assert_eq!(board.counting_all()[&Individual::Healthy], 98);
+	/// ```
+	pub fn counting_all(&self) -> HashMap<Individual, usize> {
+		let mut counting = self.population.counting_all();
+		for &(i, _, _) in &self.quarantine {
+			*counting.entry(i).or_insert(0) += 1;
+		}
+		counting
+	}
+
 	/// Returns the current state of the counting table
 	pub fn counting_table(&self) -> &CountingTable {
 		self.recording().counting_table()
 	}
+
+	/// Returns the log of what happened to each individual, 
+	/// ie visits in `visit` and state changes in `propagate`.
+	#[cfg(feature = "event-log")]
+	pub fn event_log(&self) -> &EventLog {
+		&self.event_log
+	}
+
+	/// Returns the log of what happened to each individual, so that it can be cleared.
+	#[cfg(feature = "event-log")]
+	pub fn event_log_mut(&mut self) -> &mut EventLog {
+		&mut self.event_log
+	}
 }
 
+/// Observer that does nothing.
+fn no_observer(_: Phase, _: &Board) {}
+
 impl Default for Board {
 	/// Returns an instance of `Board` with default configuration
 	///
@@ -357,13 +1161,33 @@ impl Default for Board {
 		];
 		let recording = Recording::new(population.clone(), buildings.clone());
 
-		Board{ population, buildings, inactive: Vec::new(), recording }
+		Board{ 
+			population, 
+			buildings, 
+			inactive: Vec::new(), 
+			disease: Disease::default(), 
+			flows: HashMap::new(), 
+			infections: Vec::new(), 
+			visitors: Vec::new(), 
+			testing: None, 
+			quarantine: Vec::new(), 
+			tests: 0, 
+			hospital: None, 
+			overload: 0, 
+			vaccination: None, 
+			doses: 0, 
+			day: 0, 
+			recording, 
+			#[cfg(feature = "event-log")]
+			event_log: EventLog::default(),
+		}
 	}
 }
 #[cfg(test)]
 mod tests {
 	use super::*;
 	use ndarray::array;
+	use test_case::test_case;
 
 
 	#[test]
@@ -392,8 +1216,9 @@ mod tests {
 			..default
 		};
 		board.visit();
-		let expected = vec![Individual::Infected1];
-		assert_eq!(board.inactive, expected);
+		let expected = vec![(Individual::Infected1, 0)];
+		let inactive: Vec<(Individual, usize)> = board.inactive.iter().map(|&(i, tag)| (i, tag.days)).collect();
+		assert_eq!(inactive, expected);
 	}
 
 	#[test]
@@ -428,8 +1253,12 @@ mod tests {
 		board.visit(); // Fills the inactive vector
 		board.propagate();
 		assert_eq!(board.buildings()[0], Building::unchecked_from(array![[Individual::Infected1, Individual::Infected2]]));
-		assert_eq!(board.population(), &population); // All buildings were full so the population was only shuffled!
-		assert_eq!(board.inactive, vec![Individual::Infected2, Individual::Infected2]); // Propagation at home!
+		// All buildings were full so the population was only shuffled!
+		let mut identified: Vec<(usize, Individual)> = board.population().iter_with_ids().collect();
+		identified.sort();
+		assert_eq!(identified, population.iter_with_ids().collect::<Vec<_>>());
+		let inactive: Vec<(Individual, usize)> = board.inactive.iter().map(|&(i, tag)| (i, tag.days)).collect();
+		assert_eq!(inactive, vec![(Individual::Infected2, 0), (Individual::Infected2, 0)]); // Propagation at home!
 	}
 
 	#[test]
@@ -456,6 +1285,365 @@ mod tests {
 		}
 	}
 
+	#[test]
+	fn advance_with_rng() {
+		let mut board = Board::default();
+		board.advance_many_with_rng(10, &mut crate::tests::rng(1));
+		let mut other_board = Board::default();
+		other_board.advance_many_with_rng(10, &mut crate::tests::rng(1));
+		assert_eq!(board, other_board);
+	}
+
+	#[test]
+	fn build_graph_buildings() {
+		let board_builder = BoardBuilder {
+			healthy: 3,
+			infected1: 1,
+			buildings: vec![(2, 1)],
+			graph_buildings: vec![(3, vec![(0, 1), (1, 2)])],
+			..Default::default()
+		};
+		let board = board_builder.build();
+		assert_eq!(board.buildings().len(), 2);
+		assert_eq!(board.buildings()[1].capacity(), 3);
+		assert_eq!(board.buildings()[1].neighbourhood(), &Neighbourhood::Graph(vec![(0, 1), (1, 2)]));
+	}
+
+	#[test]
+	fn advance_with_disease() {
+		let board_builder = BoardBuilder {
+			healthy: 4,
+			infected1: 1,
+			buildings: vec![(2, 1)],
+			disease: Disease::new(2, 4, Some(2), crate::disease::Recovery::Healthy),
+			..Default::default()
+		};
+		let mut board = board_builder.build();
+		let mut rng = crate::tests::rng(1);
+		let infected_course: Vec<usize> = (0..8).map(|_| {
+			board.advance_with_rng(&mut rng);
+			let counting = board.population().counting_all();
+			counting[&Individual::Infected1] + counting[&Individual::Infected2] + counting[&Individual::Infected3]
+		}).collect();
+		// The first infected stays infectious for four days, wherever it spends them
+		assert!(infected_course[..3].iter().all(|&n| n >= 1));
+		let sick = board.counting_table().inner()[&Individual::Sick].clone();
+		assert_eq!(&sick[..7], &[0, 0, 0, 0, 1, 1, 0]);
+		assert_eq!(board.population().len(), 5);
+	}
+
+	#[test]
+	fn advance_with_waning() {
+		let board_builder = BoardBuilder {
+			sick: 1,
+			disease: Disease::new(0, 3, Some(1), crate::disease::Recovery::Immune).with_immunity_days(2),
+			..Default::default()
+		};
+		let mut board = board_builder.build();
+		board.advance_many(3);
+		assert_eq!(board.counting_table().inner()[&Individual::Immune], vec![0, 1, 1, 0]);
+		assert_eq!(board.counting_table().inner()[&Individual::Healthy], vec![0, 0, 0, 1]);
+		assert_eq!(board.counting_table().flows()[&Flow::Recovery], vec![0, 1, 0, 0]);
+		assert_eq!(board.counting_table().flows()[&Flow::Waning], vec![0, 0, 0, 1]);
+		assert_eq!(board.counting_table().flows()[&Flow::Infection], vec![0, 0, 0, 0]);
+	}
+
+	#[test]
+	fn advance_with_asymptomatic() {
+		let board_builder = BoardBuilder {
+			healthy: 5,
+			asymptomatic: 1,
+			buildings: vec![(3, 2)],
+			disease: Disease::new(0, 3, None, crate::disease::Recovery::Immune).with_asymptomatic(Probability::ONE, 2),
+			..Default::default()
+		};
+		let mut board = board_builder.build();
+		board.advance_many_with_rng(10, &mut crate::tests::rng(1));
+		let counting_table = board.counting_table();
+		// Silent spread: people get infected, but nobody gets sick
+		assert!(counting_table.flows()[&Flow::Infection].iter().sum::<usize>() > 0);
+		assert!(counting_table.inner()[&Individual::Sick].iter().all(|&n| n == 0));
+		assert_eq!(counting_table.flows()[&Flow::Recovery][2], 1);
+	}
+
+	#[test_case(0, 0, 0)]
+	#[test_case(1, 1, 0)]
+	#[test_case(2, 1, 1)]
+	fn quarantine_days(quarantine_days: usize, after_test: usize, after_go_home: usize) {
+		let board_builder = BoardBuilder {
+			healthy: 1,
+			infected1: 1,
+			buildings: vec![(2, 1)],
+			testing: Some(Testing { capacity: 2, quarantine_days, ..Default::default() }),
+			..Default::default()
+		};
+		let mut board = board_builder.build();
+		let mut rng = crate::tests::rng(1);
+		board.test_with_rng(&mut rng);
+		assert_eq!(board.tests(), &2);
+		assert_eq!(board.quarantined(), after_test);
+		board.visit_with_rng(&mut rng);
+		board.propagate_with_rng(&mut rng);
+		board.go_home();
+		assert_eq!(board.quarantined(), after_go_home);
+	}
+
+	#[test]
+	fn advance_with_testing() {
+		let board_builder = BoardBuilder {
+			infected1: 2,
+			buildings: vec![(1, 1)],
+			testing: Some(Testing { capacity: 1, quarantine_days: 2, ..Default::default() }),
+			..Default::default()
+		};
+		let mut board = board_builder.build();
+		board.advance_many_with_rng(3, &mut crate::tests::rng(1));
+		assert_eq!(board.recording().tests(), &vec![0, 1, 1, 1]);
+		assert_eq!(board.counting_table().quarantined(), &vec![0, 1, 1, 1]);
+		assert_eq!(board.counting_table().days(), 4);
+		assert_eq!(board.counting_all().values().sum::<usize>(), 2);
+	}
+
+	#[test]
+	fn advance_with_infection_tree() {
+		let population = Population::from(vec![Individual::Infected1, Individual::Healthy, Individual::Healthy]);
+		let mut building = Building::new(3, 1, "Home");
+		building.set_spreading(Spreading::Everyone);
+		let mut board = Board::new(population, vec![building]);
+		board.advance_with_rng(&mut crate::tests::rng(1));
+		// Everyone met the first individual
+		assert_eq!(board.infections().len(), 2);
+		let tree = board.recording().infection_tree();
+		assert_eq!(tree.infector_of(1), Some(0));
+		assert_eq!(tree.infector_of(2), Some(0));
+		assert_eq!(tree.infector_of(0), None);
+		assert_eq!(tree.chain(2), vec![0, 2]);
+		assert!(tree.infections().iter().all(|infection| infection.building == 0 && infection.day == 1));
+	}
+
+	#[test]
+	fn advance_with_attendance() {
+		let population = Population::from(vec![Individual::Infected1, Individual::Healthy, Individual::Healthy, Individual::Healthy]);
+		let mut school = Building::new(3, 1, "School");
+		school.set_spreading(Spreading::Everyone);
+		let mut gym = Building::new(1, 1, "Gym");
+		gym.set_spreading(Spreading::Everyone);
+		let mut board = Board::new(population, vec![school, gym]);
+		board.advance_with_rng(&mut crate::tests::rng(1));
+		let attendance = board.recording().attendance();
+		assert_eq!(attendance[0].name(), "School");
+		assert_eq!(attendance[0].visitors(), &vec![0, 3]);
+		assert_eq!(attendance[1].visitors(), &vec![0, 1]);
+		// Whoever visits the gym is alone
+		assert_eq!(attendance[1].infections(), &vec![0, 0]);
+		// Everyone in the school gets infected if the infected individual is there
+		let infections = attendance[0].total_infections();
+		let expected = if attendance[0].total_infected_visitors() == 1 { 2 } else { 0 };
+		assert_eq!(infections, expected);
+		assert_eq!(infections, board.counting_table().flows()[&Flow::Infection][1]);
+	}
+
+	#[cfg(feature = "event-log")]
+	#[test]
+	fn event_log() {
+		let population = Population::from(vec![Individual::Infected1, Individual::Healthy, Individual::Sick]);
+		let mut building = Building::new(2, 1, "Home");
+		building.set_spreading(Spreading::Everyone);
+		let mut board = Board::new(population, vec![building]);
+		board.advance_many_with_rng(2, &mut crate::tests::rng(1));
+		let log = board.event_log();
+		// Everyone visits or stays home, and then steps, every day
+		assert_eq!(log.events().len(), 12);
+		assert_eq!(log.on(2).len(), 6);
+		assert_eq!(log.of(2)[0], &Event::StayHome { day: 1, id: 2 });
+		assert!(matches!(log.of(1)[0], Event::Visit { day: 1, id: 1, building: 0, .. }));
+		assert_eq!(log.of(1)[1], &Event::Step { day: 1, id: 1, before: Individual::Healthy, after: Individual::Infected1 });
+		board.event_log_mut().clear();
+		assert!(board.event_log().events().is_empty());
+	}
+
+	#[test]
+	fn advance_observed() {
+		let mut phases = Vec::new();
+		let mut observer = |phase: Phase, board: &Board| {
+			let seated = board.buildings().iter().any(|building| !building.is_empty());
+			phases.push((phase, seated));
+		};
+		let mut observed = Board::default();
+		observed.advance_observed_with_rng(&mut observer, &mut crate::tests::rng(1));
+		assert_eq!(phases, vec![(Phase::Visit, true), (Phase::Propagate, true), (Phase::GoHome, false)]);
+		// Observing does not change the game
+		let mut board = Board::default();
+		board.advance_with_rng(&mut crate::tests::rng(1));
+		assert_eq!(observed, board);
+	}
+
+	#[test]
+	fn snapshot() {
+		let board_builder = BoardBuilder {
+			healthy: 8,
+			infected1: 1,
+			sick: 1,
+			buildings: vec![(2, 2)],
+			testing: Some(Testing { capacity: 2, quarantine_days: 3, ..Default::default() }),
+			..Default::default()
+		};
+		let mut board = board_builder.build();
+		let mut rng = crate::tests::rng(1);
+		board.advance_with_rng(&mut rng);
+		// In the middle of a stage, with individuals in buildings, at home and in quarantine
+		board.test_with_rng(&mut rng);
+		board.visit_with_rng(&mut rng);
+		let snapshot = ron::to_string(&board).unwrap();
+		let mut resumed: Board = ron::from_str(&snapshot).unwrap();
+		assert_eq!(resumed, board);
+		// The same randomness gives the same future
+		board.propagate_with_rng(&mut crate::tests::rng(2));
+		resumed.propagate_with_rng(&mut crate::tests::rng(2));
+		board.go_home();
+		resumed.go_home();
+		board.advance_many_with_rng(3, &mut crate::tests::rng(3));
+		resumed.advance_many_with_rng(3, &mut crate::tests::rng(3));
+		assert_eq!(resumed, board);
+	}
+
+	#[test]
+	fn try_build() {
+		// Disease models are checked when deserializing
+		let config = "(healthy: 2, infected1: 1, buildings: [(2, 2)], disease: (infectious_days: 0))";
+		assert!(ron::from_str::<BoardBuilder>(config).is_err());
+		let board_builder = BoardBuilder {
+			buildings: vec![(2, 2), (1, 3)],
+			neighbourhood: Neighbourhood::Graph(vec![(0, 3)]),
+			..Default::default()
+		};
+		let source = BuildingError::ContactOutOfRange { seat: 3, capacity: 3 };
+		let error = BoardError::Building { building: 1, source };
+		assert_eq!(board_builder.try_build().err(), Some(error));
+	}
+
+	#[test]
+	fn try_close_occupied() {
+		let mut board = Board::default();
+		board.visit_with_rng(&mut crate::tests::rng(1));
+		let expected = board.clone();
+		assert_eq!(board.try_close("School").err(), Some(ActionError::Building(BuildingError::Occupied)));
+		assert_eq!(board.try_toggle("School").err(), Some(ActionError::Building(BuildingError::Occupied)));
+		assert_eq!(board.try_open("Zoo").err(), Some(ActionError::UnknownBuilding("Zoo".to_string())));
+		assert_eq!(board, expected);
+		board.go_home();
+		assert!(board.try_toggle("School").is_ok());
+		assert_eq!(board.apply(Action::Open("Zoo".to_string())).err(), Some(ActionError::UnknownBuilding("Zoo".to_string())));
+		assert!(board.apply(Action::Open("School".to_string())).is_ok());
+	}
+
+	#[test]
+	fn advance_with_hospital() {
+		let board_builder = BoardBuilder {
+			sick: 3,
+			hospital: Some(Hospital { beds: 1, mortality: Probability::ONE }),
+			..Default::default()
+		};
+		let mut board = board_builder.build();
+		board.advance_many(2);
+		assert_eq!(board.counting_table().overload(), &vec![0, 2, 0]);
+		assert_eq!(board.counting_table().inner()[&Individual::Dead], vec![0, 2, 2]);
+		assert_eq!(board.counting_table().inner()[&Individual::Sick], vec![3, 1, 1]);
+	}
+
+	#[test]
+	fn advance_with_vaccination() {
+		let board_builder = BoardBuilder {
+			healthy: 10,
+			infected3: 1,
+			buildings: vec![(1, 1)],
+			..Default::default()
+		};
+		for eligibility in [Eligibility::Healthy, Eligibility::Everyone] {
+			let mut board = board_builder.clone().build();
+			board.set_vaccination(Some(Vaccination { doses_per_day: 3, start_day: 2, eligibility }));
+			board.advance_many_with_rng(5, &mut crate::tests::rng(1));
+			let immune = &board.counting_table().inner()[&Individual::Immune];
+			match eligibility {
+				Eligibility::Healthy => {
+					assert_eq!(board.recording().doses(), &vec![0, 0, 3, 3, 3, 1]);
+					assert_eq!(immune, &vec![0, 0, 3, 6, 9, 10]);
+				},
+				// Some doses are wasted on immune individuals, but never on the sick one
+				Eligibility::Everyone => {
+					assert_eq!(board.recording().doses(), &vec![0, 0, 3, 3, 3, 3]);
+					assert!(immune[5] < 10);
+				},
+			}
+			assert_eq!(board.day(), &5);
+		}
+	}
+
+	#[test]
+	fn immunize_after_first_stage() {
+		let mut board = BoardBuilder { healthy: 3, buildings: vec![(1, 1)], ..Default::default() }.build();
+		board.immunize().unwrap();
+		board.advance_with_rng(&mut crate::tests::rng(1));
+		board.immunize().unwrap();
+		// Recorded days are not changed
+		assert_eq!(board.counting_table().inner()[&Individual::Immune], vec![1, 1]);
+		board.advance_with_rng(&mut crate::tests::rng(1));
+		assert_eq!(board.counting_table().inner()[&Individual::Immune], vec![1, 1, 2]);
+		assert_eq!(board.recording().doses(), &vec![0, 0, 1]);
+	}
+
+	#[test]
+	fn vaccinate_not_yet_infected() {
+		let board_builder = BoardBuilder {
+			healthy: 1,
+			sick: 1,
+			buildings: vec![(1, 1)],
+			disease: Disease::new(0, 3, Some(1), crate::disease::Recovery::Healthy),
+			..Default::default()
+		};
+		let mut board = board_builder.build();
+		board.set_vaccination(Some(Vaccination { doses_per_day: 1, start_day: 1, eligibility: Eligibility::Healthy }));
+		board.advance_many_with_rng(3, &mut crate::tests::rng(1));
+		// The sick individual recovers on the first day, but is never vaccinated afterwards
+		assert_eq!(board.recording().doses(), &vec![0, 1, 0, 0]);
+		assert_eq!(board.counting_table().inner()[&Individual::Healthy], vec![1, 1, 1, 1]);
+		assert_eq!(board.counting_table().inner()[&Individual::Immune], vec![0, 1, 1, 1]);
+	}
+
+	#[test]
+	fn apply() {
+		let population = Population::from(vec![Individual::Healthy, Individual::Infected1]);
+		let buildings = vec![Building::new(2, 1, "My bulding")];
+		let mut board = Board::new(population, buildings);
+		board.apply(Action::Close("My bulding".to_string())).unwrap();
+		assert!(!board.buildings()[0].is_open());
+		board.apply(Action::SetSpreading(Spreading::Everyone)).unwrap();
+		assert_eq!(board.spreading(), &Spreading::Everyone);
+		assert_eq!(board.apply(Action::Immunize(2)).err(), Some(crate::errors::ActionError::NoHealthyLeft));
+		assert_eq!(board.population().counting(Individual::Immune), 1);
+	}
+
+	#[test]
+	fn daily_score() {
+		let board_builder = BoardBuilder {
+			healthy: 3,
+			sick: 1,
+			immune: 1,
+			buildings: vec![(1, 1), (1, 1)],
+			penalty: 2,
+			..Default::default()
+		};
+		let mut board = board_builder.build();
+		board.advance();
+		board.close("Defult");
+		board.advance_many(2);
+		board.open("Defult");
+		board.advance();
+		assert_eq!(board.recording().penalty()[0].1, vec![0, 0, 2, 2, 0]);
+		assert_eq!(board.recording().daily_score(), &vec![0, 4, 0, 0, 4]);
+		assert_eq!(board.recording().score(), 8);
+	}
+
 	#[test]
 	#[should_panic]
 	fn close() {
@@ -2,6 +2,9 @@ use core::fmt::Display;
 use core::convert::TryFrom;
 use crate::errors::BuildingError;
 use crate::Individual;
+use crate::population::Tag;
+use crate::disease::Disease;
+use crate::probability::Probability;
 use gamma::graph::DefaultGraph;
 use ndarray::Array2;
 use serde::{Serialize, Deserialize};
@@ -26,6 +29,11 @@ pub enum Spreading {
     /// Also, as there can be more than one infected per building, they work collectively and infect
     /// as much people as possible, under the restriction that each of them infects only one other individual.
     OneVeryNear,
+    /// Infected individuals try to infect someone in their neighbourhood, see `Neighbourhood`.
+    ///
+    /// As there can be more than one infected per building, they work collectively and infect
+    /// as much people as possible, under the restriction that each of them infects only one other individual.
+    OneNeighbour,
 }
 
 impl Default for Spreading {
@@ -34,6 +42,104 @@ impl Default for Spreading {
     }
 }
 
+/// Neighbourhood of a seat in a building, ie the seats that are in contact with it.
+///
+/// Contacts are symmetric: if a seat is in the neighbourhood of another, then the converse also holds.
+///
+/// # Examples
+///
+/// Seats in the same row of a theatre.
+/// ```
+/// # use virus_alarm::building::Neighbourhood;
+/// assert_eq!(Neighbourhood::Row(2).offsets(), vec![(0, -1), (0, -2)]);
+/// ```
+#[derive(Debug, Hash, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum Neighbourhood {
+    /// Seats at distance at most `radius` verticaly, horizontaly or diagonaly
+    Moore(usize),
+    /// Seats at distance at most `radius`, counting vertical and horizontal steps
+    VonNeumann(usize),
+    /// Seats in the same row at distance at most `radius`
+    Row(usize),
+    /// Seats at the given offsets, as (rows, columns)
+    Offsets(Vec<(isize, isize)>),
+    /// Seats in contact according to a list of pairs of seats, where seats are numbered row by row.
+    ///
+    /// Since seats need not be in a grid, this neighbourhood is used by all 
+    /// spreading modes that consider spatial structure. See `BuildingBuilder::with_graph`.
+    Graph(Vec<(usize, usize)>),
+}
+
+impl Neighbourhood {
+    /// Returns the offsets, as (rows, columns), that define the neighbourhood.
+    ///
+    /// # Remarks
+    ///
+    /// Since contacts are symmetric, only one of each pair of opposite offsets is returned, 
+    /// namely the one pointing to the left, or up if it is vertical.
+    /// `Graph` neighbourhoods have no offsets.
+    pub fn offsets(&self) -> Vec<(isize, isize)> {
+        let offsets: Vec<(isize, isize)> = match self {
+            Neighbourhood::Moore(radius) => {
+                let radius = *radius as isize;
+                (-radius..=radius)
+                    .flat_map(|row| (-radius..=radius).map(move |col| (row, col)))
+                    .collect()
+            },
+            Neighbourhood::VonNeumann(radius) => {
+                let radius = *radius as isize;
+                (-radius..=radius)
+                    .flat_map(|row| (-radius..=radius).map(move |col| (row, col)))
+                    .filter(|(row, col)| row.abs() + col.abs() <= radius)
+                    .collect()
+            },
+            Neighbourhood::Row(radius) => (1..=*radius as isize).map(|col| (0, -col)).collect(),
+            Neighbourhood::Offsets(offsets) => offsets.clone(),
+            Neighbourhood::Graph(_) => Vec::new(),
+        };
+        let mut offsets: Vec<(isize, isize)> = offsets.into_iter()
+            .filter(|&offset| offset != (0, 0))
+            .map(|(row, col)| if col < 0 || (col == 0 && row < 0) { (row, col) } else { (-row, -col) })
+            .collect();
+        offsets.sort_by_key(|&(row, col)| (row.abs() + col.abs(), row.abs(), row, -col));
+        offsets.dedup();
+        offsets
+    }
+
+    /// Checks that the neighbourhood makes sense in a building with `capacity` seats.
+    ///
+    /// # Errors
+    ///
+    /// If a pair of seats in contact of a `Graph` neighbourhood 
+    /// refers to the same seat twice, or to a seat beyond `capacity`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use virus_alarm::{building::Neighbourhood, errors::BuildingError};
+    /// let neighbourhood = Neighbourhood::Graph(vec![(0, 1), (1, 1)]);
+    /// assert_eq!(neighbourhood.validate(2), Err(BuildingError::SelfContact(1)));
+    /// ```
+    pub fn validate(&self, capacity: usize) -> Result<(), BuildingError> {
+        if let Neighbourhood::Graph(contacts) = self {
+            for &(seat, other_seat) in contacts {
+                if seat == other_seat {
+                    return Err(BuildingError::SelfContact(seat));
+                }
+                if seat.max(other_seat) >= capacity {
+                    return Err(BuildingError::ContactOutOfRange { seat: seat.max(other_seat), capacity });
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Default for Neighbourhood {
+    fn default() -> Self { 
+        Neighbourhood::Moore(1)
+    }
+}
 
 /// Builder struct for `Building`.
 #[derive(Debug, Hash, Clone, PartialEq, Eq)]
@@ -43,6 +149,8 @@ pub struct BuildingBuilder {
     name: String,
     penalty: usize,
     open: bool, 
+    transmission: Probability,
+    neighbourhood: Neighbourhood,
 }
 
 impl BuildingBuilder {
@@ -74,6 +182,47 @@ impl BuildingBuilder {
         self
     }
 
+    /// Changes the probability that a contact between an infected and 
+    /// a healthy individual transmits the virus
+    pub fn with_transmission(mut self, transmission: Probability) -> Self {
+        self.transmission = transmission;
+        self
+    }
+
+    /// Changes the building to have `seats` seats, in contact according to `contacts`.
+    ///
+    /// This is useful for buildings that are not a rectangle, like restaurants with separate tables.
+    /// Contacts are checked when building, see `try_build`.
+    ///
+    /// # Remarks
+    ///
+    /// The seats are a single row, so calling `with_neighbourhood` afterwards
+    /// replaces the contacts by the neighbourhood of each seat in that row.
+    ///
+    /// # Examples
+    ///
+    /// Two tables of two people each.
+    /// ```
+    /// # use virus_alarm::BuildingBuilder;
+    /// let restaurant = BuildingBuilder::new("Restaurant")
+    ///     .with_graph(4, vec![(0, 1), (2, 3)])
+    ///     .build();
+    /// assert_eq!(restaurant.capacity(), 4);
+    /// ```
+    pub fn with_graph(mut self, seats: usize, contacts: Vec<(usize, usize)>) -> Self {
+        self.people = Array2::from_elem((1, seats), None);
+        self.neighbourhood = Neighbourhood::Graph(contacts);
+        self
+    }
+
+    /// Changes the neighbourhood of seats, used by the `OneNeighbour` spreading mode
+    ///
+    /// This replaces the contacts given by `with_graph`, if any.
+    pub fn with_neighbourhood(mut self, neighbourhood: Neighbourhood) -> Self {
+        self.neighbourhood = neighbourhood;
+        self
+    }
+
     /// Opens the building
     pub fn and_is_open(mut self) -> Self {
         self.open = true;
@@ -88,14 +237,42 @@ impl BuildingBuilder {
 
 
     /// Returns the corresponding building
+    ///
+    /// # Panics
+    ///
+    /// If the neighbourhood is not valid, see `try_build`.
     pub fn build(self) -> Building {
-        Building {
+        self.try_build().expect("creating the building failed!")
+    }
+
+    /// Returns the corresponding building, after checking that the neighbourhood is valid.
+    ///
+    /// # Errors
+    ///
+    /// If the neighbourhood is not valid for the size of the building, see `Neighbourhood::validate`.
+    ///
+    /// # Examples
+    ///
+    /// A contact with a fifth seat, in a restaurant with four seats.
+    /// ```
+    /// # use virus_alarm::{BuildingBuilder, errors::BuildingError};
+    /// let restaurant = BuildingBuilder::new("Restaurant")
+    ///     .with_graph(4, vec![(0, 1), (2, 4)])
+    ///     .try_build();
+    /// assert_eq!(restaurant.err(), Some(BuildingError::ContactOutOfRange { seat: 4, capacity: 4 }));
+    /// ```
+    pub fn try_build(self) -> Result<Building, BuildingError> {
+        self.neighbourhood.validate(self.people.len())?;
+        Ok(Building {
+            tags: Array2::default(self.people.raw_dim()),
             people: self.people,
             spreading: self.spreading,
             name: self.name,
             penalty: self.penalty,
             open: self.open,
-        }
+            transmission: self.transmission,
+            neighbourhood: self.neighbourhood,
+        })
     }
 }
 
@@ -107,18 +284,24 @@ impl Default for BuildingBuilder {
             name: String::from("Default"),
             penalty: 0,
             open: true,
+            transmission: Probability::ONE,
+            neighbourhood: Neighbourhood::default(),
         }
     }
 }
 
 /// Building in the board game where spreading can happen.
-#[derive(Debug, Hash, Clone, PartialEq, Eq, Getters, MutGetters, Setters)]
+#[derive(Debug, Hash, Clone, PartialEq, Eq, Getters, MutGetters, Setters, Serialize, Deserialize)]
 pub struct Building {
     people: Array2<Option<Individual>>,
+    /// Identifier of each individual, days it spent in its compartment and whether it has been infected
+    tags: Array2<Tag>,
     spreading: Spreading,
     name: String,
     penalty: usize,
     open: bool,
+    transmission: Probability,
+    neighbourhood: Neighbourhood,
 }
 
 impl Building {
@@ -129,6 +312,7 @@ impl Building {
         let default = Building::default();
 		Building{ 
 			people: Array2::from_elem((rows, columns), None),
+			tags: Array2::default((rows, columns)),
             name: name.to_string(),
 			..default
 		}
@@ -156,10 +340,19 @@ impl Building {
     ///
     /// If the building is open and not empty.
     pub fn toggle(&mut self) {
+        self.try_toggle().expect("toggling the building failed!");
+    }
+
+    /// Toggles (changes the open state) the building.
+    ///
+    /// # Errors
+    ///
+    /// If the building is open and not empty, in which case nothing changes.
+    pub fn try_toggle(&mut self) -> Result<&mut Self, BuildingError> {
         if self.open {
-            self.close();
+            self.try_close()
         } else {
-            self.open();
+            Ok(self.open())
         }
     }
 
@@ -169,8 +362,32 @@ impl Building {
     ///
     /// If the building is not empty
     pub fn close(&mut self) {
-        assert!(self.is_empty());
-        self.open = false;
+        self.try_close().expect("closing the building failed!");
+    }
+
+    /// Closes the building
+    ///
+    /// # Errors
+    ///
+    /// If the building is not empty, in which case nothing changes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use virus_alarm::{Building, Individual, errors::BuildingError};
+    /// let mut building = Building::new(2, 1, "Bakery");
+    /// building.try_push(Individual::Healthy).unwrap();
+    /// assert_eq!(building.try_close().err(), Some(BuildingError::Occupied));
+    /// building.empty();
+    /// assert!(building.try_close().unwrap().is_close());
+    /// ```
+    pub fn try_close(&mut self) -> Result<&mut Self, BuildingError> {
+        if self.is_empty() {
+            self.open = false;
+            Ok(self)
+        } else {
+            Err(BuildingError::Occupied)
+        }
     }
 
     /// Rerturns the penalty of the building, which is the cost of closing the building
@@ -217,6 +434,42 @@ impl Building {
 		self.spreading = new_spreading;
         self
 	}
+	/// Returns the probability that a contact between an infected and 
+	/// a healthy individual transmits the virus
+	///
+	/// The default value is one, ie contacts always transmit the virus.
+	pub fn transmission(&self) -> &Probability {
+		&self.transmission
+	}
+	/// Sets the probability that a contact between an infected and 
+	/// a healthy individual transmits the virus
+	pub fn set_transmission(&mut self, new_transmission: Probability) -> &mut Self {
+		self.transmission = new_transmission;
+        self
+	}
+	/// Returns the neighbourhood of seats, used by the `OneNeighbour` spreading mode
+	pub fn neighbourhood(&self) -> &Neighbourhood {
+		&self.neighbourhood
+	}
+	/// Sets the neighbourhood of seats, used by the `OneNeighbour` spreading mode
+	///
+	/// # Panics
+	///
+	/// If the neighbourhood is not valid, see `try_set_neighbourhood`.
+	pub fn set_neighbourhood(&mut self, new_neighbourhood: Neighbourhood) -> &mut Self {
+		self.try_set_neighbourhood(new_neighbourhood).expect("setting the neighbourhood failed!")
+	}
+	/// Sets the neighbourhood of seats, used by the `OneNeighbour` spreading mode
+	///
+	/// # Errors
+	///
+	/// If the neighbourhood is not valid for the size of the building, 
+	/// see `Neighbourhood::validate`, in which case nothing changes.
+	pub fn try_set_neighbourhood(&mut self, new_neighbourhood: Neighbourhood) -> Result<&mut Self, BuildingError> {
+		new_neighbourhood.validate(self.capacity())?;
+		self.neighbourhood = new_neighbourhood;
+        Ok(self)
+	}
     /// Return the shape of the array as a slice.
     pub fn shape(&self) -> &[usize] {
         self.people().shape()
@@ -235,155 +488,187 @@ impl Building {
     }
     /// Empties the building of people, returning the individuals that were inside
     pub fn empty(&mut self) -> Vec<Individual> {
-        let vec: Vec<Individual> = self
-            .people
-            .clone()
-            .into_iter()
-            .filter(|i| i.is_some())
-            .map(|i| i.unwrap())
-            .collect();
+        self.empty_with_tags().into_iter().map(|(i, _)| i).collect()
+    }
+    /// Empties the building of people, returning the individuals that were inside
+    /// together with their tags.
+    pub(crate) fn empty_with_tags(&mut self) -> Vec<(Individual, Tag)> {
+        let vec: Vec<(Individual, Tag)> = self.people_with_tags().collect();
         self.people.mapv_inplace(|_| -> Option<Individual> { None });
+        self.tags.fill(Tag::default());
         vec    
     }
+    /// Returns the individuals in the building together with their tags.
+    pub(crate) fn people_with_tags(&self) -> impl Iterator<Item = (Individual, Tag)> + '_ {
+        self.people.iter()
+            .zip(self.tags.iter())
+            .filter_map(|(i, &tag)| i.map(|i| (i, tag)))
+    }
     /// Appends an individual to the first available position in the building.
     ///
     /// # Errors
     ///
-    /// If the building is already full or the individual is sick.
+    /// If the building is already full or the individual is sick or dead.
     pub fn try_push(&mut self, individual: Individual) -> Result<(), BuildingError> {
+        self.try_push_with_tag(individual, Tag::default()).map(|_| ())
+    }
+
+    /// Appends an individual, together with its tag, 
+    /// to the first available position in the building,
+    /// and returns the row and column of the seat it took.
+    ///
+    /// # Errors
+    ///
+    /// If the building is already full or the individual is sick or dead.
+    pub(crate) fn try_push_with_tag(&mut self, individual: Individual, tag: Tag) -> Result<(usize, usize), BuildingError> {
         if self.is_full() {
             Err(BuildingError::Full)
         } else if individual == Individual::Sick {
         	Err(BuildingError::Sick)
+        } else if individual == Individual::Dead {
+        	Err(BuildingError::Dead)
         } else {
-            for i in self.people.iter_mut() {
+            let columns = self.people.ncols();
+            for (seat, (i, t)) in self.people.iter_mut().zip(self.tags.iter_mut()).enumerate() {
                 if i.is_none() {
                     *i = Some(individual);
-                    break;
+                    *t = tag;
+                    return Ok((seat / columns, seat % columns));
                 }
             }
-            Ok(())
+            unreachable!("a building that is not full has an empty seat")
         }
     }
 
-    /// Propagates the infection
+    /// Propagates the infection, with the disease model of the board game, see `Disease::default`.
     pub fn propagate(&mut self) -> &mut Self {
+        self.propagate_with_rng(&Disease::default(), &mut rand::thread_rng())
+    }
+
+    /// Propagates the infection, where individuals progress according to `disease`, 
+    /// drawing randomness from `rng`.
+    ///
+    /// Each contact between an infected and a healthy individual transmits the virus 
+    /// with the transmission probability of the building, and then the spreading mode
+    /// of the building is applied to the contacts that transmit. 
+    /// No randomness is drawn if contacts always transmit.
+    ///
+    /// # Examples
+    ///
+    /// Contacts never transmit.
+    /// ```
+    /// # use virus_alarm::{Building, Individual, probability::Probability, disease::Disease};
+    /// let mut building = Building::new(2, 1, "Bakery");
+    /// building.set_transmission(Probability::ZERO);
+    /// building.try_push(Individual::Infected1);
+    /// building.try_push(Individual::Healthy);
+    /// building.propagate_with_rng(&Disease::default(), &mut rand::thread_rng());
+    /// assert_eq!(building.people()[[0, 1]], Some(Individual::Healthy));
+    /// ```
+    pub fn propagate_with_rng<R: ?Sized + rand::Rng>(&mut self, disease: &Disease, rng: &mut R) -> &mut Self {
+    	self.propagate_tracing_with_rng(disease, rng);
+    	self
+    }
+
+    /// Propagates the infection according to `disease`, drawing randomness from `rng`, 
+    /// and returns the infections that happened as triples `(infector, infectee, seat)`,
+    /// where `infector` and `infectee` are identifiers and `seat` is the seat of the infectee.
+    ///
+    /// Seats are numbered row by row.
+    pub(crate) fn propagate_tracing_with_rng<R: ?Sized + rand::Rng>(&mut self, disease: &Disease, rng: &mut R) -> Vec<(usize, usize, usize)> {
     	match self.spreading {
-    		Spreading::Everyone => self.propagate_everyone(),
-    		Spreading::One => self.propagate_one(),
-    		Spreading::OneNear => self.propagate_onenear(),
-    		Spreading::OneVeryNear => self.propagate_oneverynear(),
+    		Spreading::Everyone => self.propagate_everyone(disease, rng),
+    		Spreading::One => self.propagate_one(disease, rng),
+    		Spreading::OneNear | Spreading::OneVeryNear | Spreading::OneNeighbour => self.propagate_matching(disease, rng),
     	}
     }
 
     /// Propagates by infecting one healthy individual per infected indiviual, if possible
-    fn propagate_one(&mut self) -> &mut Self{
-    	let mut counter = 0;
-    	for i in self.people.iter() {
+    fn propagate_one<R: ?Sized + rand::Rng>(&mut self, disease: &Disease, rng: &mut R) -> Vec<(usize, usize, usize)>{
+    	let mut infectors = Vec::new();
+    	for (seat, i) in self.people.iter().enumerate() {
     		if let Some(i) = i {
-    			match i {
-    				Individual::Infected1 | Individual::Infected2 | Individual::Infected3 => counter += 1,
-    				_ => (),
+    			if i.can_infect(&Individual::Healthy) && self.transmission.sample(rng) {
+    				infectors.push(seat);
     			}
-    		};
+    		}
     	}
-		self.people.mapv_inplace(|i| {
-			match i {
-				Some(Individual::Healthy) => {
-					if counter > 0 {
-						counter -= 1;
-						Some(Individual::Infected1)
-					} else {
-						Some(Individual::Healthy)
-					}
-				},
-				Some(Individual::Infected1) => Some(Individual::Infected2),
-                Some(Individual::Infected2) => Some(Individual::Infected3),
-                Some(Individual::Infected3) => Some(Individual::Sick),
-                Some(Individual::Sick) => panic!("There should not have been a sick person in the building"),
-                Some(Individual::Immune) => Some(Individual::Immune),
-                None => None,
+    	let mut infectors = infectors.into_iter();
+		let infected: Vec<Option<usize>> = self.people.iter().map(|i| {
+			if i == &Some(Individual::Healthy) {
+				infectors.next()
+			} else {
+				None
 			}
-		});
-		self
+		}).collect();
+		self.step(&infected, disease, rng)
     }
 
     /// Propagates by setting all healthy individuals to infected, if there is any infected in the building
-    fn propagate_everyone(&mut self) -> &mut Self {
-    	let mut infect_everyone = false;
-    	for i in self.people.iter() {
-    		if let Some(i) = i {
-    			match i {
-    				Individual::Infected1 | Individual::Infected2 | Individual::Infected3 => infect_everyone = true,
-    				_ => (),
-    			}
-    		};
+    fn propagate_everyone<R: ?Sized + rand::Rng>(&mut self, disease: &Disease, rng: &mut R) -> Vec<(usize, usize, usize)> {
+    	let mut infectors = Vec::new();
+    	for (seat, i) in self.people.iter().enumerate() {
+    		if matches!(i, Some(i) if i.can_infect(&Individual::Healthy)) {
+    			infectors.push(seat);
+    		}
     	}
     	
-		self.people.mapv_inplace(|i| {
-			match i {
-				Some(Individual::Healthy) => {
-					if infect_everyone {
-						Some(Individual::Infected1)
-					} else {
-						Some(Individual::Healthy)
-					}
-				},
-				Some(Individual::Infected1) => Some(Individual::Infected2),
-                Some(Individual::Infected2) => Some(Individual::Infected3),
-                Some(Individual::Infected3) => Some(Individual::Sick),
-                Some(Individual::Sick) => panic!("There should not have been a sick person in the building"),
-                Some(Individual::Immune) => Some(Individual::Immune),
-                None => None,
-			}
-		});
-		self
+    	// The first infected individual whose contact transmits is the infector
+    	let transmission = self.transmission;
+		let infected: Vec<Option<usize>> = self.people.iter()
+			.map(|i| if i == &Some(Individual::Healthy) {
+				infectors.iter().copied().find(|_| transmission.sample(rng))
+			} else {
+				None
+			})
+			.collect();
+		self.step(&infected, disease, rng)
     }
 
-    /// Propagates by choosing a maximum matching between infected and healthy individuals
-    fn propagate_onenear(&mut self) -> &mut Self {
-        let graph: DefaultGraph = self.clone().into();
+    /// Propagates by choosing a maximum matching between infected and healthy individuals 
+    /// in the contact graph, see `contact_graph`
+    fn propagate_matching<R: ?Sized + rand::Rng>(&mut self, disease: &Disease, rng: &mut R) -> Vec<(usize, usize, usize)> {
+        let transmission = self.transmission;
+        let graph = self.contact_graph(|| transmission.sample(rng));
         let mut pairing = gamma::matching::Pairing::new();
 
         gamma::matching::maximum_matching(&graph, &mut pairing);
 
-        self.propagate_from_pairing(pairing)
+        self.propagate_from_pairing(pairing, disease, rng)
     }
 
-    /// Propagates by choosing a maximum matching between infected and healthy individuals
-    fn propagate_oneverynear(&mut self) -> &mut Self {
-        let graph: DefaultGraph = self.clone().into();
-        let mut pairing = gamma::matching::Pairing::new();
-
-        gamma::matching::maximum_matching(&graph, &mut pairing);
-
-        self.propagate_from_pairing(pairing)
+    /// Propagates from a matching between infected and healthy individuals, 
+    /// where each healthy individual is infected by its mate.
+    fn propagate_from_pairing<R: ?Sized + rand::Rng>(&mut self, pairing: gamma::matching::Pairing, disease: &Disease, rng: &mut R) -> Vec<(usize, usize, usize)> {
+        let infected: Vec<Option<usize>> = (0..self.capacity())
+            .map(|seat| if pairing.has_node(seat) { Some(pairing.mate(seat)) } else { None })
+            .collect();
+        self.step(&infected, disease, rng)
     }
 
-    fn propagate_from_pairing(&mut self, pairing: gamma::matching::Pairing) -> &mut Self {
-        let rows = self.people().nrows();
-        let columns = self.people().ncols();
-        for col in 0..columns {
-            for row in 0..rows {
-                if let Some(i) = self.people()[[row, col]] {
-                    self.people[[row, col]] = Some(match i {
-                        Individual::Healthy => {
-                            if pairing.has_node(col + row * columns) {
-                                Individual::Infected1
-                            } else {
-                                Individual::Healthy
-                            }
-                        }
-                        Individual::Infected1 => Individual::Infected2,
-                        Individual::Infected2 => Individual::Infected3,
-                        Individual::Infected3 => Individual::Sick,
-                        Individual::Sick => panic!("There should not have been a sick person in the building"),
-                        Individual::Immune => Individual::Immune,
-                    });
+    /// Advances everyone in the building one day according to `disease`, 
+    /// where `infected[seat]` is the seat of the infector if the healthy individual in `seat` got infected,
+    /// and returns the infections that happened as in `propagate_tracing_with_rng`.
+    ///
+    /// Seats are numbered row by row.
+    fn step<R: ?Sized + rand::Rng>(&mut self, infected: &[Option<usize>], disease: &Disease, rng: &mut R) -> Vec<(usize, usize, usize)> {
+        let ids: Vec<usize> = self.tags.iter().map(|tag| tag.id).collect();
+        let mut infections = Vec::new();
+        for (seat, ((i, tag), &infector)) in self.people.iter_mut().zip(self.tags.iter_mut()).zip(infected).enumerate() {
+            if let Some(individual) = i {
+                if individual.stays_home() {
+                    panic!("There should not have been a {} person in the building", individual.to_string().to_lowercase());
                 }
+                if let (Individual::Healthy, Some(infector)) = (*individual, infector) {
+                    infections.push((ids[infector], tag.id, seat));
+                    tag.infected = true;
+                }
+                let (next_individual, next_days) = disease.step(*individual, tag.days, infector.is_some(), rng);
+                *individual = next_individual;
+                tag.days = next_days;
             }
         }
-        self
+        infections
     }
 
     pub fn unchecked_from<T>(array: Array2<T>) -> Self 
@@ -391,8 +676,10 @@ impl Building {
         T: Into<Option<Individual>> + Clone,
     {
         let default = Building::default();
+        let people: Array2<Option<Individual>> = array.mapv(|i| i.into());
         Building { 
-            people: array.mapv(|i| i.into()) ,
+            tags: people.mapv(|i| i.map(|i| Tag::of(0, i)).unwrap_or_default()),
+            people,
             ..default
         }
     }
@@ -408,8 +695,10 @@ impl TryFrom<Array2<Option<Individual>>> for Building {
 	type Error = BuildingError;
     fn try_from(array: Array2<Option<Individual>>) -> Result<Self, Self::Error> {
         for i in array.iter() {
-        	if let Some(Individual::Sick) = i {
-        		return Err(BuildingError::Sick)
+        	match i {
+        		Some(Individual::Sick) => return Err(BuildingError::Sick),
+        		Some(Individual::Dead) => return Err(BuildingError::Dead),
+        		_ => (),
         	}
         }
         Ok(Building::unchecked_from(array))
@@ -423,8 +712,10 @@ impl TryFrom<Array2<Individual>> for Building {
     }
 }
 
-impl Into<DefaultGraph> for Building {
-    fn into(self) -> DefaultGraph {
+impl Building {
+    /// Returns the graph of contacts between individuals that can infect each other, 
+    /// keeping only the contacts for which `transmits` returns `true`.
+    fn contact_graph<F: FnMut() -> bool>(&self, mut transmits: F) -> DefaultGraph {
         let mut graph = DefaultGraph::new();
         let rows = self.people().nrows();
         let columns = self.people().ncols();
@@ -437,69 +728,80 @@ impl Into<DefaultGraph> for Building {
             }
         }
         // Add edges
-        match self.spreading() {
-         	Spreading::OneNear | Spreading::OneVeryNear => {
-                for col in 0..columns {
-		            for row in 0..rows {
-		                if let Some(i) = self.people()[[row, col]] {
-		                	// Horizontal
-		                    if col > 0 {
-		                        if let Some(j) = self.people()[[row, col - 1]] {
-		                            if i.interacts_with(&j) {
-		                                graph
-		                                    .add_edge(col + row * columns, (col - 1) + row * columns)
-		                                    .unwrap()
-		                            }
-		                        }
-		                    }
-		                    // Vertical
-		                    if row > 0 {
-		                        if let Some(j) = self.people()[[row - 1, col]] {
-		                            if i.interacts_with(&j) {
-		                                graph
-		                                    .add_edge(col + row * columns, col + (row - 1) * columns)
-		                                    .unwrap()
-		                            }
-		                        }
-		                    }
-		                    // Diagonals
-		                    if self.spreading() == &Spreading::OneNear {
-    		                    if col > 0 && row > 0 {
-			                    	if let Some(j) = self.people()[[row - 1, col - 1]] {
-			                            if i.interacts_with(&j) {
-			                                graph
-			                                    .add_edge(col + row * columns, (col - 1) + (row - 1) * columns)
-			                                    .unwrap()
-			                            }
-			                        }
-			                    }
-			                    if col > 0 && row < rows - 1 {
-			                    	if let Some(j) = self.people()[[row + 1, col - 1]] {
-			                            if i.interacts_with(&j) {
-			                                graph
-			                                    .add_edge(col + row * columns, (col - 1) + (row + 1) * columns)
-			                                    .unwrap()
-			                            }
-			                        }
-			                    }
-		                    }
-	
-		                }
-		            }
-		        }
-         	},
-         	_ => todo!(),
-         } 
+        let offsets = match (self.spreading(), self.neighbourhood()) {
+            (Spreading::Everyone, _) | (Spreading::One, _) => {
+                // Everyone is in contact with everyone else
+                let people: Vec<Option<Individual>> = self.people().iter().copied().collect();
+                for (seat, i) in people.iter().enumerate() {
+                    for (other_seat, j) in people.iter().enumerate().take(seat) {
+                        if let (Some(i), Some(j)) = (i, j) {
+                            if i.interacts_with(j) && transmits() {
+                                graph.add_edge(seat, other_seat).unwrap()
+                            }
+                        }
+                    }
+                }
+                return graph;
+            },
+            (_, Neighbourhood::Graph(contacts)) => {
+                let mut contacts: Vec<(usize, usize)> = contacts.iter()
+                    .map(|&(seat, other_seat)| (seat.max(other_seat), seat.min(other_seat)))
+                    .collect();
+                contacts.sort_unstable();
+                contacts.dedup();
+                for (seat, other_seat) in contacts {
+                    let i = self.people()[[seat / columns, seat % columns]];
+                    let j = self.people()[[other_seat / columns, other_seat % columns]];
+                    if let (Some(i), Some(j)) = (i, j) {
+                        if i.interacts_with(&j) && transmits() {
+                            graph.add_edge(seat, other_seat).unwrap()
+                        }
+                    }
+                }
+                return graph;
+            },
+            (Spreading::OneNear, _) => Neighbourhood::Moore(1).offsets(),
+            (Spreading::OneVeryNear, _) => Neighbourhood::VonNeumann(1).offsets(),
+            (Spreading::OneNeighbour, neighbourhood) => neighbourhood.offsets(),
+        };
+        for col in 0..columns {
+            for row in 0..rows {
+                if let Some(i) = self.people()[[row, col]] {
+                    for &(row_offset, col_offset) in &offsets {
+                        let other_row = row as isize + row_offset;
+                        let other_col = col as isize + col_offset;
+                        if other_row < 0 || other_row >= rows as isize || other_col < 0 || other_col >= columns as isize {
+                            continue;
+                        }
+                        let (other_row, other_col) = (other_row as usize, other_col as usize);
+                        if let Some(j) = self.people()[[other_row, other_col]] {
+                            if i.interacts_with(&j) && transmits() {
+                                graph
+                                    .add_edge(col + row * columns, other_col + other_row * columns)
+                                    .unwrap()
+                            }
+                        }
+                    }
+                }
+            }
+        }
 		
         graph
     }
 }
 
+impl Into<DefaultGraph> for Building {
+    fn into(self) -> DefaultGraph {
+        self.contact_graph(|| true)
+    }
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
 	use test_case::test_case;
 	use ndarray::array;
+	use average::Estimate;
 
 	#[test]
 	fn construction() {
@@ -698,6 +1000,138 @@ mod tests {
 		assert_eq!(initial, expected);
 	}
 
+	#[test_case(Neighbourhood::Moore(1), vec![(0, -1), (-1, 0), (-1, -1), (1, -1)])]
+	#[test_case(Neighbourhood::VonNeumann(1), vec![(0, -1), (-1, 0)])]
+	#[test_case(Neighbourhood::VonNeumann(2), vec![(0, -1), (-1, 0), (0, -2), (-1, -1), (1, -1), (-2, 0)])]
+	#[test_case(Neighbourhood::Row(1), vec![(0, -1)])]
+	#[test_case(Neighbourhood::Offsets(vec![(0, 0), (0, 2), (0, -2), (3, 0)]), vec![(0, -2), (-3, 0)])]
+	fn offsets(neighbourhood: Neighbourhood, expected: Vec<(isize, isize)>) {
+		assert_eq!(neighbourhood.offsets(), expected);
+	}
+
+	#[test_case(Neighbourhood::Row(1), array![
+			[Individual::Infected1, Individual::Healthy],
+			[Individual::Healthy, Individual::Healthy] 
+		], array![
+			[Individual::Infected2, Individual::Infected1],
+			[Individual::Healthy, Individual::Healthy] 
+		]; "same row")]
+	#[test_case(Neighbourhood::Row(1), array![
+			[Individual::Infected1, Individual::Immune],
+			[Individual::Healthy, Individual::Healthy] 
+		], array![
+			[Individual::Infected2, Individual::Immune],
+			[Individual::Healthy, Individual::Healthy] 
+		]; "no other row")]
+	#[test_case(Neighbourhood::Moore(2), array![
+			[Individual::Infected1, Individual::Immune, Individual::Healthy],
+		], array![
+			[Individual::Infected2, Individual::Immune, Individual::Infected1],
+		]; "radius two")]
+	#[test_case(Neighbourhood::Offsets(vec![(1, -1)]), array![
+			[Individual::Healthy, Individual::Infected1],
+			[Individual::Healthy, Individual::Healthy] 
+		], array![
+			[Individual::Healthy, Individual::Infected2],
+			[Individual::Infected1, Individual::Healthy] 
+		]; "explicit offsets")]
+	fn propagate_oneneighbour(neighbourhood: Neighbourhood, initial: Array2<Individual>, expected: Array2<Individual>) {
+		let mut initial = Building::unchecked_from(initial);
+		initial.set_spreading(Spreading::OneNeighbour).set_neighbourhood(neighbourhood);
+		initial.propagate();
+		assert_eq!(initial.people(), &expected.map(|&i| Some(i)));
+	}
+
+	#[test_case(vec![(0, 2), (1, 1)], BuildingError::SelfContact(1))]
+	#[test_case(vec![(0, 2), (3, 7)], BuildingError::ContactOutOfRange { seat: 7, capacity: 4 })]
+	#[test_case(vec![(4, 0)], BuildingError::ContactOutOfRange { seat: 4, capacity: 4 })]
+	fn invalid_graph(contacts: Vec<(usize, usize)>, expected: BuildingError) {
+		let building = BuildingBuilder::new("Restaurant")
+			.with_graph(4, contacts.clone())
+			.try_build();
+		assert_eq!(building.err().as_ref(), Some(&expected));
+		let mut building = Building::new(2, 2, "Restaurant");
+		assert_eq!(building.try_set_neighbourhood(Neighbourhood::Graph(contacts)).err(), Some(expected));
+		assert_eq!(building.neighbourhood(), &Neighbourhood::default());
+	}
+
+	#[test_case(Spreading::OneNear)]
+	#[test_case(Spreading::OneVeryNear)]
+	#[test_case(Spreading::OneNeighbour)]
+	fn propagate_graph(spreading: Spreading) {
+		let mut building = BuildingBuilder::new("Restaurant")
+			.with_graph(4, vec![(0, 2), (2, 0)])
+			.with_spreading(spreading)
+			.build();
+		for &i in &[Individual::Infected1, Individual::Healthy, Individual::Healthy, Individual::Healthy] {
+			building.try_push(i).unwrap();
+		}
+		assert_eq!(building.try_push(Individual::Healthy), Err(BuildingError::Full));
+		building.propagate();
+		let expected = array![[Individual::Infected2, Individual::Healthy, Individual::Infected1, Individual::Healthy]];
+		assert_eq!(building.people(), &expected.map(|&i| Some(i)));
+	}
+
+	#[test_case(Spreading::Everyone)]
+	#[test_case(Spreading::One)]
+	fn contact_graph_of_everyone(spreading: Spreading) {
+		use gamma::graph::Graph;
+		let mut building = Building::new(2, 2, "Bakery");
+		building.set_spreading(spreading);
+		for &i in &[Individual::Healthy, Individual::Immune, Individual::Infected1, Individual::Healthy] {
+			building.try_push(i).unwrap();
+		}
+		let graph: DefaultGraph = building.into();
+		// Only contacts with the infected individual, far or near
+		assert_eq!(graph.order(), 4);
+		assert_eq!(graph.size(), 2);
+		assert_eq!(graph.has_edge(2, 0), Ok(true));
+		assert_eq!(graph.has_edge(3, 2), Ok(true));
+	}
+
+	#[test_case(Spreading::Everyone)]
+	#[test_case(Spreading::One)]
+	#[test_case(Spreading::OneNear)]
+	#[test_case(Spreading::OneVeryNear)]
+	#[test_case(Spreading::OneNeighbour)]
+	fn propagate_tracing(spreading: Spreading) {
+		let mut building = Building::new(3, 1, "Bakery");
+		building.set_spreading(spreading);
+		building.try_push_with_tag(Individual::Immune, Tag::new(5)).unwrap();
+		building.try_push_with_tag(Individual::Infected1, Tag::new(7)).unwrap();
+		building.try_push_with_tag(Individual::Healthy, Tag::new(3)).unwrap();
+		let infections = building.propagate_tracing_with_rng(&Disease::default(), &mut crate::tests::rng(1));
+		assert_eq!(infections, vec![(7, 3, 2)]);
+	}
+
+	#[test_case(Spreading::Everyone)]
+	#[test_case(Spreading::One)]
+	#[test_case(Spreading::OneNear)]
+	#[test_case(Spreading::OneVeryNear)]
+	#[test_case(Spreading::OneNeighbour)]
+	fn propagate_without_transmission(spreading: Spreading) {
+		let array = array![[Individual::Healthy, Individual::Infected1], [Individual::Healthy, Individual::Healthy]];
+		let mut building = Building::unchecked_from(array);
+		building.set_spreading(spreading).set_transmission(Probability::ZERO);
+		building.propagate_with_rng(&Disease::default(), &mut crate::tests::rng(1));
+		let expected = array![[Individual::Healthy, Individual::Infected2], [Individual::Healthy, Individual::Healthy]];
+		assert_eq!(building.people(), &expected.map(|&i| Some(i)));
+	}
+
+	#[test]
+	fn propagate_with_transmission() {
+		let mut rng = crate::tests::rng(1);
+		let transmission = Probability::try_from(0.3).unwrap();
+		let mut infected: average::Variance = average::Variance::new();
+		for _ in 0..1_000 {
+			let mut building = Building::unchecked_from(array![[Individual::Healthy, Individual::Infected1]]);
+			building.set_spreading(Spreading::OneNear).set_transmission(transmission);
+			building.propagate_with_rng(&Disease::default(), &mut rng);
+			infected.add(if building.people()[[0, 0]] == Some(Individual::Infected1) { 1. } else { 0. });
+		}
+		assert!((infected.mean() - 0.3).abs() < 4. * infected.error());
+	}
+
 	#[test_case(array![
 			[Individual::Healthy, Individual::Infected2],
 			[Individual::Healthy, Individual::Infected1] 
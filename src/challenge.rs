@@ -0,0 +1,306 @@
+use crate::{Board, Simulation, board::BoardBuilder, simulation::ReportPlan, errors::ChallengeError};
+use getset::{Getters, Setters, MutGetters};
+use serde::{Serialize, Deserialize};
+use rand::SeedableRng;
+
+/// Challenge of choosing which buildings to close for the whole game,
+/// so that the score of the game is as high as possible, see `Recording::daily_score`.
+///
+/// Every set of buildings within the budget is closed from the start,
+/// and its score is estimated by running a simulation.
+/// All sets use the same realizations of randomness, so that they are compared fairly.
+///
+/// # Remarks
+///
+/// There are two to the number of buildings sets to try,
+/// so this is only feasible for a few buildings or a small budget.
+/// Challenges with more than `MAX_SUBSETS` sets are rejected, see `Challenge::try_solve`.
+///
+/// # Examples
+///
+/// Closing the only building, where an infected individual meets everyone,
+/// is worth a small penalty.
+/// ```
+/// # use virus_alarm::prelude::*;
+/// # use virus_alarm::challenge::Challenge;
+/// let challenge = Challenge {
+///     board_builder: BoardBuilder {
+///         healthy: 8,
+///         infected1: 1,
+///         buildings: vec![(3, 3)],
+///         spreading: Spreading::Everyone,
+///         penalty: 1,
+///         ..Default::default()
+///     },
+///     report_plan: ReportPlan { num_simulations: 10, days: 5, seed: Some(1), ..Default::default() },
+///     ..Default::default()
+/// };
+/// let outcomes = challenge.solve();
+/// assert_eq!(outcomes.len(), 2);
+/// assert_eq!(outcomes[0].closed(), &vec![0]);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Getters, Setters, MutGetters, Serialize, Deserialize, Default)]
+pub struct Challenge {
+    /// Board setup
+    #[getset(get = "pub", set = "pub", get_mut = "pub")]
+    pub board_builder: BoardBuilder,
+    /// Report setup used to estimate the score of each set of closed buildings
+    #[getset(get = "pub", set = "pub", get_mut = "pub")]
+    pub report_plan: ReportPlan,
+    /// Maximum number of buildings to close
+    ///
+    /// If `None`, any set of buildings can be closed.
+    #[getset(get = "pub", set = "pub", get_mut = "pub")]
+    #[serde(default)]
+    pub budget: Option<usize>,
+    /// Penalty of closing each building for a day, in the order of the buildings of the board
+    ///
+    /// If empty, buildings have the penalty of the board builder. 
+    /// Otherwise, there must be one value per building.
+    #[getset(get = "pub", set = "pub", get_mut = "pub")]
+    #[serde(default)]
+    pub penalties: Vec<usize>,
+}
+
+/// Maximum number of sets of buildings that a challenge tries.
+pub const MAX_SUBSETS: usize = 1 << 16;
+
+/// Estimated score of closing a set of buildings, see `Challenge`.
+#[derive(Debug, Clone, Getters)]
+pub struct Outcome {
+    /// Indexes of the buildings closed, in the order of the buildings of the board
+    #[getset(get = "pub")]
+    closed: Vec<usize>,
+    /// Total score of the realizations
+    #[getset(get = "pub")]
+    score: average::Variance,
+}
+
+impl Outcome {
+    /// Returns an approximate 95% confidence interval for the expected score.
+    pub fn confidence_interval(&self) -> (f64, f64) {
+        let radius = 1.96 * self.score.error();
+        (self.score.mean() - radius, self.score.mean() + radius)
+    }
+}
+
+impl Challenge {
+    /// Returns the sets of buildings to close within the budget,
+    /// ranked from the highest to the lowest estimated score.
+    ///
+    /// If the report plan has a seed, the result is reproducible.
+    ///
+    /// # Panics
+    ///
+    /// If the challenge is not valid or has too many sets of buildings to try, see `try_solve`.
+    pub fn solve(&self) -> Vec<Outcome> {
+        self.try_solve().expect("solving the challenge failed!")
+    }
+
+    /// Returns the sets of buildings to close within the budget,
+    /// ranked from the highest to the lowest estimated score.
+    ///
+    /// If the report plan has a seed, the result is reproducible.
+    ///
+    /// # Errors
+    ///
+    /// - If the board builder is not valid, see `BoardBuilder::try_build`.
+    /// - If there are penalties, but not one per building.
+    /// - If there are more than `MAX_SUBSETS` sets of buildings to try.
+    ///
+    /// # Examples
+    ///
+    /// Seventy buildings can not be tried all together, but a budget of one building can.
+    /// ```
+    /// # use virus_alarm::prelude::*;
+    /// # use virus_alarm::{challenge::{Challenge, MAX_SUBSETS}, errors::ChallengeError};
+    /// let mut challenge = Challenge {
+    ///     board_builder: BoardBuilder { healthy: 10, buildings: vec![(1, 1); 70], ..Default::default() },
+    ///     report_plan: ReportPlan { num_simulations: 1, days: 1, ..Default::default() },
+    ///     ..Default::default()
+    /// };
+    /// assert_eq!(challenge.try_solve().err(), Some(ChallengeError::TooManySubsets(MAX_SUBSETS)));
+    /// challenge.set_budget(Some(1));
+    /// assert_eq!(challenge.try_solve().unwrap().len(), 71);
+    /// ```
+    pub fn try_solve(&self) -> Result<Vec<Outcome>, ChallengeError> {
+        match self.report_plan.seed() {
+            Some(seed) => self.try_solve_with_rng(&mut rand_pcg::Pcg64::seed_from_u64(*seed)),
+            None => self.try_solve_with_rng(&mut rand::thread_rng()),
+        }
+    }
+
+    /// Returns the sets of buildings to close within the budget,
+    /// ranked from the highest to the lowest estimated score, drawing randomness from `rng`.
+    ///
+    /// The seed of the report plan is ignored.
+    ///
+    /// # Panics
+    ///
+    /// If the challenge is not valid or has too many sets of buildings to try, see `try_solve`.
+    pub fn solve_with_rng<R: ?Sized + rand::Rng>(&self, rng: &mut R) -> Vec<Outcome> {
+        self.try_solve_with_rng(rng).expect("solving the challenge failed!")
+    }
+
+    /// Returns the sets of buildings to close within the budget,
+    /// ranked from the highest to the lowest estimated score, drawing randomness from `rng`.
+    ///
+    /// The seed of the report plan is ignored.
+    ///
+    /// # Errors
+    ///
+    /// See `try_solve`.
+    pub fn try_solve_with_rng<R: ?Sized + rand::Rng>(&self, rng: &mut R) -> Result<Vec<Outcome>, ChallengeError> {
+        let board = self.board()?;
+        let n = board.buildings().len();
+        let budget = self.budget.unwrap_or(n).min(n);
+        if !matches!(num_subsets(n, budget), Some(num) if num <= MAX_SUBSETS) {
+            return Err(ChallengeError::TooManySubsets(MAX_SUBSETS));
+        }
+        let seed: u64 = rng.gen();
+        let mut outcomes: Vec<Outcome> = subsets(n, budget)
+            .map(|closed| {
+                let mut board = board.clone();
+                for &index in &closed {
+                    board.buildings_mut()[index].close();
+                }
+                let simulation = Simulation::new(board, self.report_plan.clone());
+                let report = simulation.run_with_rng(&mut rand_pcg::Pcg64::seed_from_u64(seed));
+                let score = report.score_average().expect("scoring the simulation failed!");
+                Outcome { closed, score }
+            })
+            .collect();
+        outcomes.sort_by(|a, b| b.score.mean().total_cmp(&a.score.mean()));
+        Ok(outcomes)
+    }
+
+    /// Returns the board of the challenge, with the penalties of the challenge.
+    ///
+    /// # Errors
+    ///
+    /// See `try_solve`.
+    fn board(&self) -> Result<Board, ChallengeError> {
+        let mut board = self.board_builder.clone().try_build()?;
+        if self.penalties.is_empty() {
+            return Ok(board);
+        }
+        let expected = board.buildings().len();
+        if self.penalties.len() != expected {
+            return Err(ChallengeError::PenaltiesMismatch { expected, found: self.penalties.len() });
+        }
+        for (building, &penalty) in board.buildings_mut().iter_mut().zip(&self.penalties) {
+            building.set_penalty(penalty);
+        }
+        Ok(board)
+    }
+}
+
+/// Returns the number of subsets of `0..n` with at most `budget` elements, 
+/// or `None` if it does not fit in a `usize`.
+fn num_subsets(n: usize, budget: usize) -> Option<usize> {
+    let mut binomial: usize = 1;
+    let mut total: usize = 1;
+    for k in 1..=budget.min(n) {
+        // Exact, since the product of k consecutive numbers is divisible by k!
+        binomial = binomial.checked_mul(n - k + 1)? / k;
+        total = total.checked_add(binomial)?;
+    }
+    Some(total)
+}
+
+/// Returns all subsets of `0..n` with at most `budget` elements, smallest first.
+fn subsets(n: usize, budget: usize) -> impl Iterator<Item = Vec<usize>> {
+    (0..=budget.min(n)).flat_map(move |k| Combinations::new(n, k))
+}
+
+/// Iterator over the subsets of `0..n` with `k` elements, in lexicographic order.
+struct Combinations {
+    n: usize,
+    next: Option<Vec<usize>>,
+}
+
+impl Combinations {
+    fn new(n: usize, k: usize) -> Self {
+        let next = if k <= n { Some((0..k).collect()) } else { None };
+        Combinations { n, next }
+    }
+}
+
+impl Iterator for Combinations {
+    type Item = Vec<usize>;
+    fn next(&mut self) -> Option<Vec<usize>> {
+        let current = self.next.take()?;
+        let k = current.len();
+        // Rightmost position that can still be increased
+        if let Some(i) = (0..k).rev().find(|&i| current[i] < self.n - k + i) {
+            let mut next = current.clone();
+            next[i] += 1;
+            for j in i + 1..k {
+                next[j] = next[j - 1] + 1;
+            }
+            self.next = Some(next);
+        }
+        Some(current)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::errors::{BoardError, BuildingError};
+    use test_case::test_case;
+
+    #[test_case(3, 3, 8)]
+    #[test_case(3, 1, 4)]
+    #[test_case(3, 0, 1)]
+    #[test_case(0, 0, 1)]
+    #[test_case(5, 2, 16)]
+    fn subsets(n: usize, budget: usize, expected: usize) {
+        let subsets: Vec<Vec<usize>> = super::subsets(n, budget).collect();
+        assert_eq!(subsets.len(), expected);
+        assert_eq!(num_subsets(n, budget), Some(expected));
+        // Distinct, increasing and smallest first
+        assert!(subsets.iter().all(|subset| subset.windows(2).all(|pair| pair[0] < pair[1])));
+        assert!(subsets.windows(2).all(|pair| pair[0].len() < pair[1].len() || pair[0] < pair[1]));
+    }
+
+    #[test]
+    fn num_subsets_large() {
+        assert_eq!(num_subsets(64, 64), None);
+        assert_eq!(num_subsets(100, 1), Some(101));
+        assert_eq!(num_subsets(100, 2), Some(5051));
+    }
+
+    #[test]
+    fn solve() {
+        let challenge = Challenge {
+            board_builder: BoardBuilder { healthy: 4, buildings: vec![(2, 2), (1, 1)], ..Default::default() },
+            report_plan: ReportPlan { num_simulations: 3, days: 2, ..Default::default() },
+            budget: Some(1),
+            penalties: vec![3, 0],
+        };
+        let outcomes = challenge.solve_with_rng(&mut crate::tests::rng(1));
+        // Nobody is infected, so closing is only penalized
+        let scores: Vec<(Vec<usize>, f64)> = outcomes.iter()
+            .map(|outcome| (outcome.closed().clone(), outcome.score().mean()))
+            .collect();
+        assert_eq!(scores, vec![(vec![], 8.), (vec![1], 8.), (vec![0], 2.)]);
+        assert_eq!(outcomes[0].confidence_interval(), (8., 8.));
+    }
+
+    #[test]
+    fn invalid() {
+        let mut challenge = Challenge {
+            board_builder: BoardBuilder { healthy: 4, buildings: vec![(2, 2), (1, 1)], ..Default::default() },
+            report_plan: ReportPlan { num_simulations: 1, days: 1, ..Default::default() },
+            budget: None,
+            penalties: vec![3],
+        };
+        let error = ChallengeError::PenaltiesMismatch { expected: 2, found: 1 };
+        assert_eq!(challenge.try_solve_with_rng(&mut crate::tests::rng(1)).err(), Some(error));
+        challenge.penalties = Vec::new();
+        challenge.board_builder.graph_buildings = vec![(2, vec![(0, 0)])];
+        let error = ChallengeError::Board(BoardError::Building { building: 2, source: BuildingError::SelfContact(0) });
+        assert_eq!(challenge.try_solve_with_rng(&mut crate::tests::rng(1)).err(), Some(error));
+    }
+}
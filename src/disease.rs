@@ -0,0 +1,577 @@
+use crate::Individual;
+use crate::probability::Probability;
+use crate::recording::Flow;
+use crate::errors::DiseaseError;
+use core::convert::TryFrom;
+use serde::{Serialize, Deserialize};
+use getset::{CopyGetters, Setters};
+
+/// Compartment of individuals after they recover from being sick.
+#[derive(Debug, Hash, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, Default)]
+pub enum Recovery {
+    /// Recovered individuals can not be infected again
+    #[default]
+    Immune,
+    /// Recovered individuals can be infected again
+    Healthy,
+}
+
+impl From<Recovery> for Individual {
+    fn from(recovery: Recovery) -> Individual {
+        match recovery {
+            Recovery::Immune => Individual::Immune,
+            Recovery::Healthy => Individual::Healthy,
+        }
+    }
+}
+
+/// Disease model, ie how infected individuals progress through the compartments.
+///
+/// Each day, every individual takes one step of the model:
+/// - Newly infected individuals become `Exposed`, or `Infected1` if there is no incubation.
+/// - `Exposed` individuals become `Infected1` after `incubation_days` days.
+/// - Infectious individuals are `Infected1` in their first day, `Infected3` in their last day
+///   and `Infected2` in between, so they become `Sick` after `infectious_days` days.
+/// - A fraction `asymptomatic` of infectious individuals are `Asymptomatic` instead, 
+///   who never get sick and recover after `asymptomatic_days` days.
+/// - `Sick` individuals recover after `sick_days` days, if any.
+/// - `Immune` individuals become `Healthy` after `immunity_days` days, if any.
+///
+/// Individuals remember how many days they spent in their compartment, see `step`.
+///
+/// # Default
+///
+/// The dynamics of the board game: no incubation, three infectious days, 
+/// no asymptomatic individuals and absorbing sickness.
+/// ```
+/// # use virus_alarm::disease::Disease;
+/// let disease = Disease::default();
+/// assert_eq!(disease.incubation_days(), 0);
+/// assert_eq!(disease.infectious_days(), 3);
+/// assert_eq!(disease.sick_days(), None);
+/// assert_eq!(disease.immunity_days(), None);
+/// ```
+#[derive(Debug, Hash, Copy, Clone, PartialEq, Eq, CopyGetters, Setters, Serialize, Deserialize)]
+#[serde(try_from = "UncheckedDisease")]
+pub struct Disease {
+    /// Days an individual is infected but not yet infectious, ie `Exposed`
+    #[getset(get_copy = "pub", set = "pub")]
+    incubation_days: usize,
+    /// Days an individual is infectious, at least one
+    #[getset(get_copy = "pub")]
+    infectious_days: usize,
+    /// Days an individual is sick before recovering, at least one
+    ///
+    /// If `None`, sick individuals never recover.
+    #[getset(get_copy = "pub")]
+    sick_days: Option<usize>,
+    /// Compartment of individuals after they recover from being sick or asymptomatic
+    #[getset(get_copy = "pub", set = "pub")]
+    recovery: Recovery,
+    /// Probability that an infectious individual is asymptomatic, ie it never gets sick
+    #[getset(get_copy = "pub")]
+    asymptomatic: Probability,
+    /// Days an individual is asymptomatic before recovering, at least one if there are asymptomatic individuals
+    #[getset(get_copy = "pub")]
+    asymptomatic_days: usize,
+    /// Days an individual is immune, either after recovering or after being vaccinated, at least one
+    ///
+    /// If `None`, immunity never wanes.
+    #[getset(get_copy = "pub")]
+    immunity_days: Option<usize>,
+}
+
+/// Disease model as written in configurations, before checking that it is valid, see `Disease::validate`.
+#[derive(Deserialize)]
+#[serde(default)]
+struct UncheckedDisease {
+    incubation_days: usize,
+    infectious_days: usize,
+    sick_days: Option<usize>,
+    recovery: Recovery,
+    asymptomatic: Probability,
+    asymptomatic_days: usize,
+    immunity_days: Option<usize>,
+}
+
+impl Default for UncheckedDisease {
+    fn default() -> Self {
+        let disease = Disease::default();
+        UncheckedDisease {
+            incubation_days: disease.incubation_days,
+            infectious_days: disease.infectious_days,
+            sick_days: disease.sick_days,
+            recovery: disease.recovery,
+            asymptomatic: disease.asymptomatic,
+            asymptomatic_days: disease.asymptomatic_days,
+            immunity_days: disease.immunity_days,
+        }
+    }
+}
+
+impl TryFrom<UncheckedDisease> for Disease {
+    type Error = DiseaseError;
+    fn try_from(unchecked: UncheckedDisease) -> Result<Self, Self::Error> {
+        let disease = Disease {
+            incubation_days: unchecked.incubation_days,
+            infectious_days: unchecked.infectious_days,
+            sick_days: unchecked.sick_days,
+            recovery: unchecked.recovery,
+            asymptomatic: unchecked.asymptomatic,
+            asymptomatic_days: unchecked.asymptomatic_days,
+            immunity_days: unchecked.immunity_days,
+        };
+        disease.validate()?;
+        Ok(disease)
+    }
+}
+
+impl Disease {
+    /// Creates a new disease model.
+    ///
+    /// # Panics
+    ///
+    /// If there are no infectious days or no sick days, see `try_new`.
+    pub fn new(incubation_days: usize, infectious_days: usize, sick_days: Option<usize>, recovery: Recovery) -> Self {
+        Disease::try_new(incubation_days, infectious_days, sick_days, recovery).expect("creating the disease failed!")
+    }
+
+    /// Creates a new disease model.
+    ///
+    /// # Errors
+    ///
+    /// If there are no infectious days, or `sick_days` is `Some(0)`, see `validate`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use virus_alarm::{disease::{Disease, Recovery}, errors::DiseaseError};
+    /// let disease = Disease::try_new(0, 0, None, Recovery::Immune);
+    /// assert_eq!(disease, Err(DiseaseError::NoInfectiousDays));
+    /// ```
+    pub fn try_new(incubation_days: usize, infectious_days: usize, sick_days: Option<usize>, recovery: Recovery) -> Result<Self, DiseaseError> {
+        let disease = Disease { 
+            incubation_days, 
+            infectious_days, 
+            sick_days, 
+            recovery, 
+            asymptomatic: Probability::ZERO,
+            asymptomatic_days: infectious_days,
+            immunity_days: None,
+        };
+        disease.validate()?;
+        Ok(disease)
+    }
+
+    /// Checks that every compartment lasts at least one day.
+    ///
+    /// Every way of creating or changing a disease model goes through this check,
+    /// including deserialization.
+    ///
+    /// # Errors
+    ///
+    /// - If there are no infectious days.
+    /// - If there are asymptomatic individuals, but no asymptomatic days.
+    /// - If `sick_days` or `immunity_days` is `Some(0)`.
+    pub fn validate(&self) -> Result<(), DiseaseError> {
+        if self.infectious_days == 0 {
+            Err(DiseaseError::NoInfectiousDays)
+        } else if self.asymptomatic != Probability::ZERO && self.asymptomatic_days == 0 {
+            Err(DiseaseError::NoAsymptomaticDays)
+        } else if self.sick_days == Some(0) {
+            Err(DiseaseError::NoSickDays)
+        } else if self.immunity_days == Some(0) {
+            Err(DiseaseError::NoImmunityDays)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Changes the probability that an infectious individual is asymptomatic, 
+    /// and the number of days it is asymptomatic before recovering.
+    ///
+    /// # Panics
+    ///
+    /// If there are asymptomatic individuals, but no asymptomatic days.
+    ///
+    /// # Examples
+    ///
+    /// Half of infectious individuals never get sick, and recover after five days.
+    /// ```
+    /// # use core::convert::TryFrom;
+    /// # use virus_alarm::{Individual, probability::Probability, disease::{Disease, Recovery}};
+    /// let disease = Disease::default().with_asymptomatic(Probability::try_from(0.5).unwrap(), 5);
+    /// let mut rng = rand::thread_rng();
+    /// assert_eq!(disease.step(Individual::Asymptomatic, 4, false, &mut rng), (Individual::Immune, 0));
+    /// ```
+    pub fn with_asymptomatic(mut self, asymptomatic: Probability, asymptomatic_days: usize) -> Self {
+        self.try_change(|disease| {
+            disease.asymptomatic = asymptomatic;
+            disease.asymptomatic_days = asymptomatic_days;
+        }).expect("changing the asymptomatic individuals failed!");
+        self
+    }
+
+    /// Changes the number of days an individual is immune before becoming healthy again.
+    ///
+    /// # Panics
+    ///
+    /// If there are no immunity days.
+    ///
+    /// # Examples
+    ///
+    /// Sick individuals recover after a week and are immune for a month.
+    /// ```
+    /// # use virus_alarm::{Individual, disease::{Disease, Recovery}};
+    /// let disease = Disease::new(0, 3, Some(7), Recovery::Immune).with_immunity_days(30);
+    /// assert_eq!(disease.step(Individual::Immune, 29, false, &mut rand::thread_rng()), (Individual::Healthy, 0));
+    /// ```
+    pub fn with_immunity_days(mut self, immunity_days: usize) -> Self {
+        self.set_immunity_days(Some(immunity_days)).expect("changing the immunity days failed!");
+        self
+    }
+
+    /// Changes the number of days an individual is infectious.
+    ///
+    /// # Errors
+    ///
+    /// If the disease would not be valid, see `validate`, in which case nothing changes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use virus_alarm::{disease::Disease, errors::DiseaseError};
+    /// let mut disease = Disease::default();
+    /// assert_eq!(disease.set_infectious_days(0).err(), Some(DiseaseError::NoInfectiousDays));
+    /// assert_eq!(disease.infectious_days(), 3);
+    /// ```
+    pub fn set_infectious_days(&mut self, infectious_days: usize) -> Result<&mut Self, DiseaseError> {
+        self.try_change(|disease| disease.infectious_days = infectious_days)
+    }
+
+    /// Changes the number of days an individual is sick before recovering.
+    ///
+    /// # Errors
+    ///
+    /// If the disease would not be valid, see `validate`, in which case nothing changes.
+    pub fn set_sick_days(&mut self, sick_days: Option<usize>) -> Result<&mut Self, DiseaseError> {
+        self.try_change(|disease| disease.sick_days = sick_days)
+    }
+
+    /// Changes the probability that an infectious individual is asymptomatic.
+    ///
+    /// # Errors
+    ///
+    /// If the disease would not be valid, see `validate`, in which case nothing changes.
+    pub fn set_asymptomatic(&mut self, asymptomatic: Probability) -> Result<&mut Self, DiseaseError> {
+        self.try_change(|disease| disease.asymptomatic = asymptomatic)
+    }
+
+    /// Changes the number of days an individual is asymptomatic before recovering.
+    ///
+    /// # Errors
+    ///
+    /// If the disease would not be valid, see `validate`, in which case nothing changes.
+    pub fn set_asymptomatic_days(&mut self, asymptomatic_days: usize) -> Result<&mut Self, DiseaseError> {
+        self.try_change(|disease| disease.asymptomatic_days = asymptomatic_days)
+    }
+
+    /// Changes the number of days an individual is immune before becoming healthy again.
+    ///
+    /// # Errors
+    ///
+    /// If the disease would not be valid, see `validate`, in which case nothing changes.
+    pub fn set_immunity_days(&mut self, immunity_days: Option<usize>) -> Result<&mut Self, DiseaseError> {
+        self.try_change(|disease| disease.immunity_days = immunity_days)
+    }
+
+    /// Applies `change` if the resulting disease is valid, see `validate`.
+    fn try_change<F: FnOnce(&mut Disease)>(&mut self, change: F) -> Result<&mut Self, DiseaseError> {
+        let mut disease = *self;
+        change(&mut disease);
+        disease.validate()?;
+        *self = disease;
+        Ok(self)
+    }
+
+    /// Returns `true` if an individual that has spent `days` days in its compartment was infected during the last day.
+    pub fn is_newly_infected(&self, individual: Individual, days: usize) -> bool {
+        let compartment = if self.incubation_days > 0 {
+            individual == Individual::Exposed
+        } else {
+            matches!(individual, Individual::Infected1 | Individual::Asymptomatic)
+        };
+        compartment && days == 0
+    }
+
+    /// Returns the compartment of individuals who become infectious, drawing randomness from `rng`.
+    ///
+    /// No randomness is drawn if there are no asymptomatic individuals.
+    fn infectious<R: ?Sized + rand::Rng>(&self, rng: &mut R) -> Individual {
+        if self.asymptomatic.sample(rng) {
+            Individual::Asymptomatic
+        } else {
+            Individual::Infected1
+        }
+    }
+
+    /// Returns `true` if individuals need to remember the number of days they spent in their compartment.
+    ///
+    /// This is not the case for the board game, where every compartment lasts either one day or forever.
+    pub fn needs_timers(&self) -> bool {
+        self.incubation_days > 1
+            || self.infectious_days > 3
+            || (self.asymptomatic != Probability::ZERO && self.asymptomatic_days > 1)
+            || matches!(self.sick_days, Some(days) if days > 1)
+            || matches!(self.immunity_days, Some(days) if days > 1)
+    }
+
+    /// Returns the flow that an individual, that has spent `days` days in its compartment, 
+    /// takes part in during the day, unless it gets infected.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use virus_alarm::{Individual, disease::{Disease, Recovery}, recording::Flow};
+    /// let disease = Disease::new(0, 3, Some(2), Recovery::Healthy);
+    /// assert_eq!(disease.flow(Individual::Sick, 0), None);
+    /// assert_eq!(disease.flow(Individual::Sick, 1), Some(Flow::Recovery));
+    /// ```
+    pub fn flow(&self, individual: Individual, days: usize) -> Option<Flow> {
+        let ends = |duration: Option<usize>| matches!(duration, Some(duration) if days + 1 >= duration);
+        match individual {
+            Individual::Sick if ends(self.sick_days) => Some(Flow::Recovery),
+            Individual::Asymptomatic if ends(Some(self.asymptomatic_days)) => Some(Flow::Recovery),
+            Individual::Immune if ends(self.immunity_days) => Some(Flow::Waning),
+            _ => None,
+        }
+    }
+
+    /// Advances one day an individual that has spent `days` days in its compartment,
+    /// where `infected` is `true` if the individual was infected during the day.
+    /// Randomness is drawn from `rng` only to decide if individuals who become infectious are asymptomatic.
+    ///
+    /// Returns the new compartment and the number of days spent in it.
+    ///
+    /// # Examples
+    ///
+    /// The board game.
+    /// ```
+    /// # use virus_alarm::{Individual, disease::Disease};
+    /// let disease = Disease::default();
+    /// let mut rng = rand::thread_rng();
+    /// assert_eq!(disease.step(Individual::Healthy, 0, true, &mut rng), (Individual::Infected1, 0));
+    /// assert_eq!(disease.step(Individual::Infected1, 0, false, &mut rng), (Individual::Infected2, 0));
+    /// assert_eq!(disease.step(Individual::Infected3, 0, false, &mut rng), (Individual::Sick, 0));
+    /// assert_eq!(disease.step(Individual::Sick, 0, false, &mut rng), (Individual::Sick, 0));
+    /// ```
+    pub fn step<R: ?Sized + rand::Rng>(&self, individual: Individual, days: usize, infected: bool, rng: &mut R) -> (Individual, usize) {
+        match individual {
+            Individual::Healthy => {
+                if !infected {
+                    (Individual::Healthy, 0)
+                } else if self.incubation_days > 0 {
+                    (Individual::Exposed, 0)
+                } else {
+                    (self.infectious(rng), 0)
+                }
+            },
+            Individual::Exposed => {
+                if days + 1 < self.incubation_days {
+                    (Individual::Exposed, days + 1)
+                } else {
+                    (self.infectious(rng), 0)
+                }
+            },
+            Individual::Infected1 => match self.infectious_days {
+                0 | 1 => (Individual::Sick, 0),
+                2 => (Individual::Infected3, 0),
+                _ => (Individual::Infected2, 0),
+            },
+            Individual::Infected2 => {
+                if days + 3 < self.infectious_days {
+                    (Individual::Infected2, days + 1)
+                } else {
+                    (Individual::Infected3, 0)
+                }
+            },
+            Individual::Infected3 => (Individual::Sick, 0),
+            Individual::Asymptomatic => {
+                if days + 1 < self.asymptomatic_days {
+                    (Individual::Asymptomatic, days + 1)
+                } else {
+                    (self.recovery.into(), 0)
+                }
+            },
+            Individual::Sick => match self.sick_days {
+                Some(sick_days) if days + 1 >= sick_days => (self.recovery.into(), 0),
+                Some(_) => (Individual::Sick, days + 1),
+                None => (Individual::Sick, 0),
+            },
+            Individual::Immune => match self.immunity_days {
+                Some(immunity_days) if days + 1 >= immunity_days => (Individual::Healthy, 0),
+                Some(_) => (Individual::Immune, days + 1),
+                None => (Individual::Immune, 0),
+            },
+            Individual::Dead => (Individual::Dead, 0),
+        }
+    }
+}
+
+impl Default for Disease {
+    fn default() -> Self {
+        Disease::new(0, 3, None, Recovery::Immune)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    /// Follows an individual for `days` days after getting infected.
+    fn course(disease: &Disease, days: usize) -> Vec<Individual> {
+        let mut rng = crate::tests::rng(1);
+        let mut state = disease.step(Individual::Healthy, 0, true, &mut rng);
+        let mut course = vec![state.0];
+        for _ in 1..days {
+            state = disease.step(state.0, state.1, false, &mut rng);
+            course.push(state.0);
+        }
+        course
+    }
+
+    #[test]
+    fn board_game() {
+        let expected = vec![
+            Individual::Infected1,
+            Individual::Infected2,
+            Individual::Infected3,
+            Individual::Sick,
+            Individual::Sick,
+        ];
+        assert_eq!(course(&Disease::default(), 5), expected);
+    }
+
+    #[test]
+    fn incubation() {
+        let disease = Disease::new(2, 1, None, Recovery::Immune);
+        let expected = vec![
+            Individual::Exposed,
+            Individual::Exposed,
+            Individual::Infected1,
+            Individual::Sick,
+        ];
+        assert_eq!(course(&disease, 4), expected);
+    }
+
+    #[test]
+    fn infectious_days() {
+        let disease = Disease::new(0, 5, None, Recovery::Immune);
+        let expected = vec![
+            Individual::Infected1,
+            Individual::Infected2,
+            Individual::Infected2,
+            Individual::Infected2,
+            Individual::Infected3,
+            Individual::Sick,
+        ];
+        assert_eq!(course(&disease, 6), expected);
+        let disease = Disease::new(0, 2, None, Recovery::Immune);
+        assert_eq!(course(&disease, 3), vec![Individual::Infected1, Individual::Infected3, Individual::Sick]);
+    }
+
+    #[test_case(Recovery::Immune)]
+    #[test_case(Recovery::Healthy)]
+    fn recovery(recovery: Recovery) {
+        let disease = Disease::new(0, 1, Some(2), recovery);
+        let expected = vec![
+            Individual::Infected1,
+            Individual::Sick,
+            Individual::Sick,
+            recovery.into(),
+        ];
+        assert_eq!(course(&disease, 4), expected);
+    }
+
+    #[test]
+    fn waning() {
+        let disease = Disease::new(0, 1, Some(1), Recovery::Immune).with_immunity_days(2);
+        let expected = vec![
+            Individual::Infected1,
+            Individual::Sick,
+            Individual::Immune,
+            Individual::Immune,
+            Individual::Healthy,
+            Individual::Healthy,
+        ];
+        assert_eq!(course(&disease, 6), expected);
+    }
+
+    #[test]
+    fn asymptomatic() {
+        let disease = Disease::new(1, 3, None, Recovery::Healthy).with_asymptomatic(Probability::ONE, 2);
+        let expected = vec![
+            Individual::Exposed,
+            Individual::Asymptomatic,
+            Individual::Asymptomatic,
+            Individual::Healthy,
+        ];
+        assert_eq!(course(&disease, 4), expected);
+    }
+
+    #[test]
+    fn asymptomatic_fraction() {
+        let disease = Disease::default().with_asymptomatic(Probability::try_from(0.3).unwrap(), 3);
+        let mut rng = crate::tests::rng(1);
+        let asymptomatic: average::Variance = (0..1_000)
+            .map(|_| disease.step(Individual::Healthy, 0, true, &mut rng).0)
+            .map(|i| if i == Individual::Asymptomatic { 1. } else { 0. })
+            .collect();
+        assert!((asymptomatic.mean() - 0.3).abs() < 4. * asymptomatic.error());
+    }
+
+    #[test_case(Individual::Asymptomatic, 2, Some(Flow::Recovery))]
+    #[test_case(Individual::Sick, 0, None)]
+    #[test_case(Individual::Sick, 2, Some(Flow::Recovery))]
+    #[test_case(Individual::Immune, 3, None)]
+    #[test_case(Individual::Immune, 4, Some(Flow::Waning))]
+    #[test_case(Individual::Infected3, 0, None)]
+    fn flow(individual: Individual, days: usize, expected: Option<Flow>) {
+        let disease = Disease::new(0, 3, Some(3), Recovery::Immune).with_immunity_days(5);
+        assert_eq!(disease.flow(individual, days), expected);
+    }
+
+    #[test_case(Disease::default(), false)]
+    #[test_case(Disease::default().with_immunity_days(1), false)]
+    #[test_case(Disease::default().with_immunity_days(2), true)]
+    #[test_case(Disease::new(1, 2, Some(1), Recovery::Healthy), false)]
+    #[test_case(Disease::new(2, 3, None, Recovery::Immune), true)]
+    #[test_case(Disease::new(0, 4, None, Recovery::Immune), true)]
+    #[test_case(Disease::new(0, 3, Some(5), Recovery::Immune), true)]
+    fn needs_timers(disease: Disease, expected: bool) {
+        assert_eq!(disease.needs_timers(), expected);
+    }
+
+    #[test_case("(infectious_days: 0)", DiseaseError::NoInfectiousDays)]
+    #[test_case("(asymptomatic: 0.5, asymptomatic_days: 0)", DiseaseError::NoAsymptomaticDays)]
+    #[test_case("(sick_days: Some(0))", DiseaseError::NoSickDays)]
+    #[test_case("(immunity_days: Some(0))", DiseaseError::NoImmunityDays)]
+    fn deserialize_invalid(config: &str, expected: DiseaseError) {
+        let error = ron::from_str::<Disease>(config).unwrap_err();
+        assert!(error.to_string().contains(&expected.to_string()));
+    }
+
+    #[test]
+    fn validate() {
+        // Asymptomatic days do not matter without asymptomatic individuals
+        let disease: Disease = ron::from_str("(asymptomatic_days: 0)").unwrap();
+        assert_eq!(disease.validate(), Ok(()));
+        let mut disease = Disease::default();
+        assert_eq!(disease.set_asymptomatic(Probability::ONE).unwrap().asymptomatic_days(), 3);
+        assert_eq!(disease.set_asymptomatic_days(0).err(), Some(DiseaseError::NoAsymptomaticDays));
+        assert_eq!(disease.set_sick_days(Some(0)).err(), Some(DiseaseError::NoSickDays));
+        assert_eq!(disease, Disease::default().with_asymptomatic(Probability::ONE, 3));
+        assert_eq!(Disease::try_new(0, 3, Some(0), Recovery::Immune), Err(DiseaseError::NoSickDays));
+    }
+}
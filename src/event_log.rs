@@ -0,0 +1,156 @@
+use crate::Individual;
+use getset::Getters;
+use serde::{Serialize, Deserialize};
+
+/// Something that happened to an individual during a stage of the game.
+///
+/// Individuals are referred to by their identifiers, see `Population::iter_with_ids`.
+#[derive(Debug, Hash, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Event {
+    /// The individual sat in a building, when visiting
+    Visit {
+        /// Day of the stage
+        day: usize,
+        /// Identifier of the individual
+        id: usize,
+        /// Index of the building, in the order of the buildings of the board
+        building: usize,
+        /// Row and column of the seat in the building
+        seat: (usize, usize),
+    },
+    /// The individual stayed at home, when visiting
+    ///
+    /// This happens if it is sick or dead, or if there was no seat left.
+    StayHome {
+        /// Day of the stage
+        day: usize,
+        /// Identifier of the individual
+        id: usize,
+    },
+    /// The individual went from one state to another, when propagating
+    ///
+    /// The states may coincide.
+    Step {
+        /// Day of the stage
+        day: usize,
+        /// Identifier of the individual
+        id: usize,
+        /// State before propagating
+        before: Individual,
+        /// State after propagating
+        after: Individual,
+    },
+}
+
+impl Event {
+    /// Returns the day of the stage in which the event happened.
+    pub fn day(&self) -> usize {
+        match *self {
+            Event::Visit { day, .. } | Event::StayHome { day, .. } | Event::Step { day, .. } => day,
+        }
+    }
+
+    /// Returns the identifier of the individual the event happened to.
+    pub fn id(&self) -> usize {
+        match *self {
+            Event::Visit { id, .. } | Event::StayHome { id, .. } | Event::Step { id, .. } => id,
+        }
+    }
+}
+
+/// Log of the events of every individual during the game, see `Board::event_log`.
+///
+/// # Examples
+///
+/// Following one individual.
+/// ```
+/// # use virus_alarm::prelude::*;
+/// let mut board = Board::default();
+/// board.advance_many(3);
+/// let events = board.event_log().of(0);
+/// // One visit and one step per day
+/// assert_eq!(events.len(), 6);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Getters, Serialize, Deserialize, Default)]
+pub struct EventLog {
+    /// Returns all events, in the order they happened.
+    #[getset(get = "pub")]
+    events: Vec<Event>,
+}
+
+impl EventLog {
+    /// Returns the events of individual `id`, in the order they happened.
+    pub fn of(&self, id: usize) -> Vec<&Event> {
+        self.events.iter().filter(|event| event.id() == id).collect()
+    }
+
+    /// Returns the events of `day`, in the order they happened.
+    pub fn on(&self, day: usize) -> Vec<&Event> {
+        self.events.iter().filter(|event| event.day() == day).collect()
+    }
+
+    /// Removes all events.
+    pub fn clear(&mut self) {
+        self.events.clear()
+    }
+
+    /// Adds an event at the end of the log.
+    pub(crate) fn push(&mut self, event: Event) {
+        self.events.push(event)
+    }
+
+    /// Writes the events on the writer, one per row,
+    /// with columns day, id, event, building, row, column, before and after.
+    ///
+    /// Columns that do not apply to an event are left empty.
+    ///
+    /// # Remarks
+    ///
+    /// Recall that a writer needs to be flushed to show in the output stream.
+    pub fn write_on<W: std::io::Write>(&self, writer: W) -> csv::Result<csv::Writer<W>> {
+        let mut writer = csv::Writer::from_writer(writer);
+        writer.serialize(["Day", "Id", "Event", "Building", "Row", "Column", "Before", "After"])?;
+        for event in &self.events {
+            let row: [String; 8] = match *event {
+                Event::Visit { day, id, building, seat: (row, column) } => [
+                    day.to_string(), id.to_string(), "Visit".to_string(),
+                    building.to_string(), row.to_string(), column.to_string(), String::new(), String::new(),
+                ],
+                Event::StayHome { day, id } => [
+                    day.to_string(), id.to_string(), "StayHome".to_string(),
+                    String::new(), String::new(), String::new(), String::new(), String::new(),
+                ],
+                Event::Step { day, id, before, after } => [
+                    day.to_string(), id.to_string(), "Step".to_string(),
+                    String::new(), String::new(), String::new(), before.to_string(), after.to_string(),
+                ],
+            };
+            writer.serialize(row)?;
+        }
+        Ok(writer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn write_on() -> csv::Result<()> {
+        let mut event_log = EventLog::default();
+        event_log.push(Event::Visit { day: 1, id: 0, building: 2, seat: (3, 4) });
+        event_log.push(Event::StayHome { day: 1, id: 1 });
+        event_log.push(Event::Step { day: 1, id: 0, before: Individual::Healthy, after: Individual::Exposed });
+        let writer = event_log.write_on(vec![])?;
+        let data = String::from_utf8(writer.into_inner().unwrap()).unwrap();
+        assert_eq!(data, String::from("\
+            Day,Id,Event,Building,Row,Column,Before,After\n\
+            1,0,Visit,2,3,4,,\n\
+            1,1,StayHome,,,,,\n\
+            1,0,Step,,,,Healthy,Exposed\n\
+        "));
+        assert_eq!(event_log.of(1), vec![&Event::StayHome { day: 1, id: 1 }]);
+        assert_eq!(event_log.on(2), Vec::<&Event>::new());
+        Ok(())
+    }
+}
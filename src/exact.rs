@@ -0,0 +1,324 @@
+use std::collections::{BTreeMap, HashMap};
+use core::convert::TryFrom;
+use crate::errors::MarkovChainError;
+use crate::{Board, Individual, Population, board::BoardBuilder, recording::CountingTable};
+use getset::Getters;
+use strum::IntoEnumIterator;
+
+/// State of the game up to the order of the population,
+/// ie the number of individuals of each variant.
+pub type State = BTreeMap<Individual, usize>;
+
+/// Exact solver for the dynamics of a board, seen as a finite Markov chain over `State`s.
+///
+/// # Remarks
+///
+/// Transitions are computed by enumerating all possible seatings of the population,
+/// so this is only feasible for small populations and buildings.
+/// Transitions are computed at most once per state.
+/// Not every board is supported, see `MarkovChain::try_from`.
+///
+/// # Examples
+///
+/// One infected and two healthy individuals, with only two seats next to each other.
+/// ```
+/// # use virus_alarm::prelude::*;
+/// # use virus_alarm::exact::MarkovChain;
+/// let board_builder = BoardBuilder {
+///     healthy: 2,
+///     infected1: 1,
+///     buildings: vec![(2, 1)],
+///     spreading: Spreading::OneNear,
+///     ..Default::default()
+/// };
+/// let mut markov_chain = MarkovChain::new(board_builder);
+/// let distribution = markov_chain.distribution(1);
+/// assert!((distribution.individual_mean(&Individual::Healthy) - 4. / 3.).abs() < 1e-10);
+/// ```
+#[derive(Debug, Clone, Getters)]
+pub struct MarkovChain {
+    /// Board whose dynamics are computed
+    #[getset(get = "pub")]
+    board: Board,
+    transitions: HashMap<State, Vec<(State, f64)>>,
+}
+
+impl MarkovChain {
+    /// Creates the Markov chain of the board given by `board_builder`.
+    ///
+    /// # Panics
+    ///
+    /// If the board is not supported, see `MarkovChain::try_from`.
+    pub fn new(board_builder: BoardBuilder) -> Self {
+        MarkovChain::try_from(board_builder.build()).expect("creating the Markov chain failed!")
+    }
+
+    /// Returns the state of the board at the start.
+    pub fn initial_state(&self) -> State {
+        to_state(self.board.population())
+    }
+
+    /// Returns the possible states after one day, starting from `state`, with their probabilities.
+    pub fn transition(&mut self, state: &State) -> &Vec<(State, f64)> {
+        if !self.transitions.contains_key(state) {
+            let transition = self.compute_transition(state);
+            self.transitions.insert(state.clone(), transition);
+        }
+        &self.transitions[state]
+    }
+
+    /// Returns the distribution of the state of the board after `days` days.
+    pub fn distribution(&mut self, days: usize) -> Distribution {
+        let mut distribution: HashMap<State, f64> = vec![(self.initial_state(), 1.)].into_iter().collect();
+        for _ in 0..days {
+            let mut next: HashMap<State, f64> = HashMap::new();
+            for (state, probability) in distribution {
+                for (next_state, transition_probability) in self.transition(&state) {
+                    *next.entry(next_state.clone()).or_insert(0.) += probability * transition_probability;
+                }
+            }
+            distribution = next;
+        }
+        Distribution { inner: distribution }
+    }
+
+    /// Enumerates all seatings of the population in the open buildings.
+    fn compute_transition(&self, state: &State) -> Vec<(State, f64)> {
+        let capacity: usize = self.board.buildings().iter()
+            .filter(|building| building.is_open())
+            .map(|building| building.capacity())
+            .sum();
+        let mut not_sick: State = state.iter()
+            .filter(|(&i, &n)| !i.stays_home() && n > 0)
+            .map(|(&i, &n)| (i, n))
+            .collect();
+        let seated = capacity.min(not_sick.values().sum());
+
+        let mut transition: HashMap<State, f64> = HashMap::new();
+        let mut seating = Vec::with_capacity(seated);
+        self.enumerate_seatings(state, &mut not_sick, &mut seating, seated, 1., &mut transition);
+        transition.into_iter().collect()
+    }
+
+    /// Recursively chooses who takes the next seat, accumulating the resulting states in `transition`.
+    fn enumerate_seatings(
+        &self,
+        state: &State,
+        remaining: &mut State,
+        seating: &mut Vec<Individual>,
+        seated: usize,
+        probability: f64,
+        transition: &mut HashMap<State, f64>,
+    ) {
+        if seating.len() == seated {
+            let next_state = self.advance_seating(state, seating);
+            *transition.entry(next_state).or_insert(0.) += probability;
+            return;
+        }
+        let total: usize = remaining.values().sum();
+        let variants: Vec<Individual> = remaining.iter()
+            .filter(|(_, &n)| n > 0)
+            .map(|(&i, _)| i)
+            .collect();
+        for individual in variants {
+            let count = remaining[&individual];
+            *remaining.get_mut(&individual).unwrap() -= 1;
+            seating.push(individual);
+            self.enumerate_seatings(state, remaining, seating, seated, probability * count as f64 / total as f64, transition);
+            seating.pop();
+            *remaining.get_mut(&individual).unwrap() += 1;
+        }
+    }
+
+    /// Advances the board one day, where `seating` is the order in which individuals take the seats.
+    fn advance_seating(&self, state: &State, seating: &[Individual]) -> State {
+        let mut population_vec = seating.to_vec();
+        for (&individual, &n) in state {
+            let already_seated = seating.iter().filter(|&&i| i == individual).count();
+            population_vec.append(&mut vec![individual; n - already_seated]);
+        }
+        let mut board = self.board.clone();
+        *board.population_mut() = Population::from(population_vec);
+        board.advance_population_in_order();
+        to_state(board.population())
+    }
+}
+
+/// Creates the Markov chain of a board.
+///
+/// # Errors
+///
+/// If contacts in some building do not always transmit the virus, see `Building::transmission`,
+/// if individuals need to remember the days spent in their compartment, see `Disease::needs_timers`,
+/// if some individuals are asymptomatic, see `Disease::asymptomatic`,
+/// or if the board has testing, a hospital or vaccination.
+///
+/// # Examples
+///
+/// ```
+/// # use core::convert::TryFrom;
+/// # use virus_alarm::prelude::*;
+/// # use virus_alarm::{exact::MarkovChain, hospital::Hospital, errors::MarkovChainError};
+/// let mut board = Board::default();
+/// board.set_hospital(Some(Hospital::default()));
+/// assert_eq!(MarkovChain::try_from(board).err(), Some(MarkovChainError::Hospital));
+/// ```
+impl TryFrom<Board> for MarkovChain {
+    type Error = MarkovChainError;
+    fn try_from(board: Board) -> Result<Self, Self::Error> {
+        if !board.buildings().iter().all(|building| building.transmission().is_certain()) {
+            return Err(MarkovChainError::UncertainTransmission);
+        }
+        if board.disease().needs_timers() {
+            return Err(MarkovChainError::Timers);
+        }
+        if board.disease().asymptomatic() != crate::probability::Probability::ZERO {
+            return Err(MarkovChainError::Asymptomatic);
+        }
+        if board.testing().is_some() {
+            return Err(MarkovChainError::Testing);
+        }
+        if board.hospital().is_some() {
+            return Err(MarkovChainError::Hospital);
+        }
+        if board.vaccination().is_some() {
+            return Err(MarkovChainError::Vaccination);
+        }
+        Ok(MarkovChain { board, transitions: HashMap::new() })
+    }
+}
+
+/// Probability distribution over the states of the game.
+#[derive(Debug, Clone, PartialEq, Getters, Default)]
+pub struct Distribution {
+    /// Probability of each state, where states of probability zero are omitted.
+    #[getset(get = "pub")]
+    inner: HashMap<State, f64>,
+}
+
+impl Distribution {
+    /// Returns the probability of `state`.
+    pub fn probability(&self, state: &State) -> f64 {
+        self.inner.get(state).copied().unwrap_or(0.)
+    }
+
+    /// Returns the probability that the outbreak is contained, see `CountingTable::is_contained`.
+    pub fn containment_probability(&self) -> f64 {
+        self.inner.iter()
+            .filter(|(state, _)| {
+                let counting_table: CountingTable = state.iter().map(|(&i, &n)| (i, vec![n])).collect();
+                counting_table.is_contained()
+            })
+            .map(|(_, probability)| probability)
+            .sum()
+    }
+
+    /// Returns the distribution of the number of individuals of a variant.
+    pub fn individual(&self, individual: &Individual) -> BTreeMap<usize, f64> {
+        let mut marginal = BTreeMap::new();
+        for (state, probability) in &self.inner {
+            *marginal.entry(state[individual]).or_insert(0.) += probability;
+        }
+        marginal
+    }
+
+    /// Returns the expected number of individuals of a variant.
+    pub fn individual_mean(&self, individual: &Individual) -> f64 {
+        self.inner.iter()
+            .map(|(state, probability)| state[individual] as f64 * probability)
+            .sum()
+    }
+}
+
+/// Counts the individuals of each variant in the population.
+fn to_state(population: &Population) -> State {
+    let counting = population.counting_all();
+    Individual::iter().map(|i| (i, counting[&i])).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{building::Spreading, simulation::{ReportPlan, Simulation}};
+
+    fn state(counts: [usize; 9]) -> State {
+        Individual::iter().zip(counts.iter().copied()).collect()
+    }
+
+    #[test]
+    fn transition() {
+        let board_builder = BoardBuilder {
+            healthy: 2,
+            infected1: 1,
+            buildings: vec![(2, 1)],
+            spreading: Spreading::OneNear,
+            ..Default::default()
+        };
+        let mut markov_chain = MarkovChain::new(board_builder);
+        let initial_state = markov_chain.initial_state();
+        assert_eq!(initial_state, state([2, 0, 1, 0, 0, 0, 0, 0, 0]));
+        let distribution = markov_chain.distribution(1);
+        assert!((distribution.probability(&state([1, 0, 1, 1, 0, 0, 0, 0, 0])) - 2. / 3.).abs() < 1e-10);
+        assert!((distribution.probability(&state([2, 0, 0, 1, 0, 0, 0, 0, 0])) - 1. / 3.).abs() < 1e-10);
+    }
+
+    #[test]
+    fn containment_probability() {
+        let board_builder = BoardBuilder {
+            healthy: 1,
+            infected1: 1,
+            buildings: vec![(1, 1)],
+            spreading: Spreading::OneNear,
+            ..Default::default()
+        };
+        let mut markov_chain = MarkovChain::new(board_builder);
+        assert_eq!(markov_chain.distribution(2).containment_probability(), 0.);
+        assert_eq!(markov_chain.distribution(3).containment_probability(), 1.);
+    }
+
+    #[test]
+    fn unsupported() {
+        use crate::{probability::Probability, disease::{Disease, Recovery}, testing::Testing, hospital::Hospital, vaccination::Vaccination};
+        let board = || BoardBuilder { healthy: 2, infected1: 1, buildings: vec![(2, 1)], ..Default::default() }.build();
+        let half = Probability::try_from(0.5).unwrap();
+
+        let mut uncertain = board();
+        uncertain.set_transmission(half);
+        assert_eq!(MarkovChain::try_from(uncertain).err(), Some(MarkovChainError::UncertainTransmission));
+        let mut timers = board();
+        timers.set_disease(Disease::new(0, 3, Some(2), Recovery::Immune));
+        assert_eq!(MarkovChain::try_from(timers).err(), Some(MarkovChainError::Timers));
+        let mut asymptomatic = board();
+        asymptomatic.set_disease(Disease::default().with_asymptomatic(half, 1));
+        assert_eq!(MarkovChain::try_from(asymptomatic).err(), Some(MarkovChainError::Asymptomatic));
+        let mut testing = board();
+        testing.set_testing(Some(Testing::default()));
+        assert_eq!(MarkovChain::try_from(testing).err(), Some(MarkovChainError::Testing));
+        let mut hospital = board();
+        hospital.set_hospital(Some(Hospital::default()));
+        assert_eq!(MarkovChain::try_from(hospital).err(), Some(MarkovChainError::Hospital));
+        let mut vaccination = board();
+        vaccination.set_vaccination(Some(Vaccination::default()));
+        assert_eq!(MarkovChain::try_from(vaccination).err(), Some(MarkovChainError::Vaccination));
+        assert!(MarkovChain::try_from(board()).is_ok());
+    }
+
+    #[test]
+    fn monte_carlo() {
+        let board_builder = BoardBuilder {
+            healthy: 5,
+            infected1: 1,
+            immune: 1,
+            buildings: vec![(2, 2)],
+            spreading: Spreading::OneVeryNear,
+            ..Default::default()
+        };
+        let mut markov_chain = MarkovChain::new(board_builder.clone());
+        let exact = markov_chain.distribution(4).individual_mean(&Individual::Healthy);
+
+        let report_plan = ReportPlan { num_simulations: 2_000, days: 4, ..Default::default() };
+        let report = Simulation::new(board_builder.build(), report_plan).run_with_rng(&mut crate::tests::rng(1));
+        let approximation: average::Variance = report.individual_last(&Individual::Healthy).iter().map(|&&n| n as f64).collect();
+        assert!((approximation.mean() - exact).abs() < 4. * approximation.error());
+    }
+}
@@ -0,0 +1,233 @@
+use std::collections::VecDeque;
+use core::fmt::Display;
+use crate::{Board, errors::{ActionError, HistoryError}, policy::Action};
+use getset::{Getters, CopyGetters};
+use serde::{Serialize, Deserialize};
+
+/// Game played interactively, where changes to the board can be undone.
+///
+/// Before each change, a snapshot of the board is kept, up to `capacity` snapshots,
+/// so that mistakes can be undone with `undo` and then redone with `redo`.
+/// Snapshots are whole boards, so the recording of the game, and in particular
+/// the counting table and the timeline, always matches the state of the board.
+///
+/// # Examples
+///
+/// Undo a mistaken closure and rewind the game.
+/// ```
+/// # use virus_alarm::prelude::*;
+/// # use virus_alarm::history::History;
+/// let mut history = History::new(Board::default(), 10);
+/// history.advance_many(3);
+/// history.toggle("School").unwrap();
+/// assert!(history.board().buildings()[2].is_close());
+/// history.undo().unwrap();
+/// assert!(history.board().buildings()[2].is_open());
+/// history.rewind_to(1).unwrap();
+/// assert_eq!(history.board().day(), &1);
+/// assert_eq!(history.board().counting_table().days(), 2);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Getters, CopyGetters, Serialize, Deserialize)]
+pub struct History {
+    /// Current state of the game
+    #[getset(get = "pub")]
+    board: Board,
+    /// Previous states of the game, from the oldest to the most recent
+    past: VecDeque<Board>,
+    /// States of the game that were undone, from the last one to be redone to the first one
+    future: Vec<Board>,
+    /// Maximum number of previous states that are kept
+    #[getset(get_copy = "pub")]
+    capacity: usize,
+}
+
+impl History {
+    /// Creates a history that starts at `board` and keeps up to `capacity` previous states.
+    pub fn new(board: Board, capacity: usize) -> Self {
+        History { board, past: VecDeque::new(), future: Vec::new(), capacity }
+    }
+
+    /// Changes the board with `change`, keeping a snapshot of the previous state,
+    /// and returns the output of `change`.
+    ///
+    /// After a change, states that were undone can not be redone anymore.
+    /// If the board did not change, no snapshot is kept.
+    pub fn change<T, F: FnOnce(&mut Board) -> T>(&mut self, change: F) -> T {
+        let previous = self.board.clone();
+        let output = change(&mut self.board);
+        if self.board != previous {
+            self.past.push_back(previous);
+            if self.past.len() > self.capacity {
+                self.past.pop_front();
+            }
+            self.future.clear();
+        }
+        output
+    }
+
+    /// Advance a stage in the game, see `Board::advance`.
+    pub fn advance(&mut self) -> &mut Self {
+        self.advance_with_rng(&mut rand::thread_rng())
+    }
+
+    /// Advance a stage in the game, drawing randomness from `rng`, see `Board::advance_with_rng`.
+    pub fn advance_with_rng<R: ?Sized + rand::Rng>(&mut self, rng: &mut R) -> &mut Self {
+        self.change(|board| { board.advance_with_rng(rng); });
+        self
+    }
+
+    /// Advance the specified number of stages in the game, each of which can be undone.
+    pub fn advance_many(&mut self, num_stages: usize) -> &mut Self {
+        self.advance_many_with_rng(num_stages, &mut rand::thread_rng())
+    }
+
+    /// Advance the specified number of stages in the game, each of which can be undone,
+    /// drawing randomness from `rng`.
+    pub fn advance_many_with_rng<R: ?Sized + rand::Rng>(&mut self, num_stages: usize, rng: &mut R) -> &mut Self {
+        for _ in 0..num_stages {
+            self.advance_with_rng(rng);
+        }
+        self
+    }
+
+    /// Toggles the buildings with the given name, see `Board::try_toggle`.
+    ///
+    /// # Errors
+    ///
+    /// If there is no building with the given name, or if one of them is open and not empty.
+    /// In that case, nothing changes and no snapshot is kept.
+    pub fn toggle<S: Display>(&mut self, name: S) -> Result<&mut Self, ActionError> {
+        self.change(|board| board.try_toggle(name).map(|_| ()))?;
+        Ok(self)
+    }
+
+    /// Immunize one person in the population, see `Board::immunize`.
+    ///
+    /// # Errors
+    ///
+    /// If there is no healthy individual to immunize.
+    pub fn immunize(&mut self) -> Result<&mut Self, ActionError> {
+        self.change(|board| board.immunize().map(|_| ()))?;
+        Ok(self)
+    }
+
+    /// Takes an action on the board, see `Board::apply`.
+    ///
+    /// # Errors
+    ///
+    /// If there are not enough healthy individuals to immunize.
+    /// In that case, all healthy individuals are immunized, and this can be undone.
+    pub fn apply(&mut self, action: Action) -> Result<&mut Self, ActionError> {
+        self.change(|board| board.apply(action).map(|_| ()))?;
+        Ok(self)
+    }
+
+    /// Returns `true` if there is a previous state to go back to.
+    pub fn can_undo(&self) -> bool {
+        !self.past.is_empty()
+    }
+
+    /// Returns `true` if there is an undone state to go forward to.
+    pub fn can_redo(&self) -> bool {
+        !self.future.is_empty()
+    }
+
+    /// Goes back to the state before the last change.
+    ///
+    /// # Errors
+    ///
+    /// If there is no previous state, either because nothing changed or
+    /// because it was beyond the capacity.
+    pub fn undo(&mut self) -> Result<&mut Self, HistoryError> {
+        let previous = self.past.pop_back().ok_or(HistoryError::NothingToUndo)?;
+        self.future.push(core::mem::replace(&mut self.board, previous));
+        Ok(self)
+    }
+
+    /// Goes forward to the state before the last undo.
+    ///
+    /// # Errors
+    ///
+    /// If nothing was undone since the last change.
+    pub fn redo(&mut self) -> Result<&mut Self, HistoryError> {
+        let next = self.future.pop().ok_or(HistoryError::NothingToRedo)?;
+        self.past.push_back(core::mem::replace(&mut self.board, next));
+        Ok(self)
+    }
+
+    /// Goes back to the most recent state in `day`, undoing every change after it.
+    ///
+    /// Rewinding can be redone step by step with `redo`.
+    ///
+    /// # Errors
+    ///
+    /// If no state of `day` is kept, in which case nothing changes.
+    pub fn rewind_to(&mut self, day: usize) -> Result<&mut Self, HistoryError> {
+        if self.board.day() != &day && self.past.iter().all(|board| board.day() != &day) {
+            return Err(HistoryError::DayNotKept(day));
+        }
+        while self.board.day() != &day {
+            self.undo()?;
+        }
+        Ok(self)
+    }
+
+    /// Returns the current state of the game, dropping the history.
+    pub fn into_board(self) -> Board {
+        self.board
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn undo_redo() {
+        let mut history = History::new(Board::default(), 2);
+        let initial = history.board().clone();
+        history.immunize().unwrap();
+        history.advance_with_rng(&mut crate::tests::rng(1));
+        let advanced = history.board().clone();
+        history.advance_with_rng(&mut crate::tests::rng(2));
+        // Only two previous states are kept
+        history.undo().unwrap().undo().unwrap();
+        assert_eq!(history.undo().err(), Some(HistoryError::NothingToUndo));
+        assert_eq!(history.board().counting_table().days(), 1);
+        assert_ne!(history.board(), &initial);
+        history.redo().unwrap();
+        assert_eq!(history.board(), &advanced);
+        assert_eq!(history.board().recording().timeline(), &1);
+        // Changing drops the undone states
+        history.toggle("School").unwrap();
+        assert_eq!(history.redo().err(), Some(HistoryError::NothingToRedo));
+    }
+
+    #[test]
+    fn unchanged() {
+        let mut history = History::new(Board::default(), 10);
+        assert_eq!(history.toggle("Nowhere").err(), Some(ActionError::UnknownBuilding("Nowhere".to_string())));
+        assert!(!history.can_undo());
+        let mut history = History::new(Board::new(crate::Population::from(vec![crate::Individual::Sick]), vec![]), 10);
+        assert_eq!(history.immunize().err(), Some(ActionError::NoHealthyLeft));
+        assert!(!history.can_undo());
+    }
+
+    #[test]
+    fn rewind_to() {
+        let mut history = History::new(Board::default(), 10);
+        history.advance_many_with_rng(2, &mut crate::tests::rng(1));
+        history.toggle("School").unwrap();
+        history.advance_many_with_rng(2, &mut crate::tests::rng(2));
+        assert_eq!(history.rewind_to(7).err(), Some(HistoryError::DayNotKept(7)));
+        assert_eq!(history.board().day(), &4);
+        history.rewind_to(2).unwrap();
+        // The most recent state of day two, where the school is closed
+        assert_eq!(history.board().day(), &2);
+        assert!(history.board().buildings()[2].is_close());
+        assert_eq!(history.board().counting_table().days(), 3);
+        assert_eq!(history.board().recording().timeline(), &2);
+        history.redo().unwrap();
+        assert_eq!(history.board().day(), &3);
+    }
+}
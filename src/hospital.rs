@@ -0,0 +1,64 @@
+use crate::probability::Probability;
+use getset::{Getters, Setters, MutGetters};
+use serde::{Serialize, Deserialize};
+
+/// Hospital with a limited number of beds for sick individuals.
+///
+/// Each day, sick individuals beyond the number of beds overload the hospital,
+/// and each of them dies with probability `mortality`, see `Board::propagate`.
+///
+/// # Examples
+///
+/// Five beds, where one out of ten sick individuals without a bed dies each day.
+/// ```
+/// # use core::convert::TryFrom;
+/// # use virus_alarm::{hospital::Hospital, probability::Probability};
+/// let hospital = Hospital {
+///     beds: 5,
+///     mortality: Probability::try_from(0.1).unwrap(),
+/// };
+/// assert_eq!(hospital.overload(7), 2);
+/// ```
+#[derive(Debug, Hash, Clone, PartialEq, Eq, Getters, Setters, MutGetters, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Hospital {
+    /// Number of beds for sick individuals
+    #[getset(get = "pub", set = "pub", get_mut = "pub")]
+    pub beds: usize,
+    /// Probability that a sick individual without a bed dies each day
+    ///
+    /// If zero, overload is only recorded.
+    #[getset(get = "pub", set = "pub", get_mut = "pub")]
+    pub mortality: Probability,
+}
+
+impl Hospital {
+    /// Returns the number of sick individuals without a bed.
+    pub fn overload(&self, sick: usize) -> usize {
+        sick.saturating_sub(self.beds)
+    }
+}
+
+impl Default for Hospital {
+    /// No beds, but nobody dies.
+    fn default() -> Self {
+        Hospital {
+            beds: 0,
+            mortality: Probability::ZERO,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    #[test_case(0, 0)]
+    #[test_case(3, 0)]
+    #[test_case(4, 1)]
+    fn overload(sick: usize, expected: usize) {
+        let hospital = Hospital { beds: 3, ..Default::default() };
+        assert_eq!(hospital.overload(sick), expected);
+    }
+}
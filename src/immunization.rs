@@ -0,0 +1,242 @@
+use core::convert::TryFrom;
+use crate::{Board, Individual, Simulation, board::BoardBuilder, probability::Probability, recording::CountingTable, simulation::ReportPlan};
+use average::Estimate;
+use getset::{Getters, Setters, MutGetters};
+use serde::{Serialize, Deserialize};
+use rand::SeedableRng;
+
+/// Goal of immunizing individuals before the game, see `ImmunizationSearch`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Target {
+    /// Probability that the outbreak is contained in the last day, see `CountingTable::is_contained`
+    Containment(Probability),
+    /// Expected number of healthy or immune individuals in the last day
+    Healthy(usize),
+}
+
+impl Target {
+    /// Returns the value of a realization, whose average is compared with the threshold.
+    fn value(&self, counting_table: &CountingTable) -> f64 {
+        match self {
+            Target::Containment(_) => if counting_table.is_contained() { 1. } else { 0. },
+            Target::Healthy(_) => {
+                let day = counting_table.days() - 1;
+                (counting_table.get(&Individual::Healthy, day) + counting_table.get(&Individual::Immune, day)) as f64
+            },
+        }
+    }
+
+    /// Returns the minimum average value that reaches the target.
+    fn threshold(&self) -> f64 {
+        match self {
+            Target::Containment(probability) => probability.value(),
+            Target::Healthy(healthy) => *healthy as f64,
+        }
+    }
+}
+
+impl Default for Target {
+    /// Contain the outbreak with probability 0.95.
+    fn default() -> Self {
+        Target::Containment(Probability::try_from(0.95).unwrap())
+    }
+}
+
+/// Search of the minimum number of individuals to immunize before the game to reach a target.
+///
+/// Immunized individuals are chosen as in `Board::immunize`, and each number of them
+/// is evaluated by running simulations in batches of the size of the report plan.
+/// Batches are added until an approximate 95% confidence interval decides whether the target is reached,
+/// or until `max_simulations` realizations have been run, in which case the average decides.
+///
+/// # Remarks
+///
+/// The search is a bisection, which assumes that immunizing more individuals never hurts.
+///
+/// # Examples
+///
+/// Everyone meets in a single building, so the infected individual must not meet anyone healthy.
+/// ```
+/// # use virus_alarm::prelude::*;
+/// # use virus_alarm::immunization::{ImmunizationSearch, Target};
+/// let search = ImmunizationSearch {
+///     board_builder: BoardBuilder {
+///         healthy: 5,
+///         infected1: 1,
+///         buildings: vec![(3, 2)],
+///         spreading: Spreading::Everyone,
+///         ..Default::default()
+///     },
+///     report_plan: ReportPlan { num_simulations: 10, days: 5, seed: Some(1), ..Default::default() },
+///     target: Target::Healthy(5),
+///     max_simulations: 100,
+/// };
+/// assert_eq!(search.minimum_doses(), Some(5));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Getters, Setters, MutGetters, Serialize, Deserialize, Default)]
+pub struct ImmunizationSearch {
+    /// Board setup, before immunizing anyone
+    #[getset(get = "pub", set = "pub", get_mut = "pub")]
+    pub board_builder: BoardBuilder,
+    /// Report setup, whose number of simulations is the size of each batch
+    #[getset(get = "pub", set = "pub", get_mut = "pub")]
+    pub report_plan: ReportPlan,
+    /// Goal of the immunization
+    #[getset(get = "pub", set = "pub", get_mut = "pub")]
+    #[serde(default)]
+    pub target: Target,
+    /// Maximum number of realizations to evaluate each number of immunized individuals
+    ///
+    /// At least one batch is always run.
+    #[getset(get = "pub", set = "pub", get_mut = "pub")]
+    #[serde(default)]
+    pub max_simulations: usize,
+}
+
+impl ImmunizationSearch {
+    /// Returns the minimum number of individuals to immunize to reach the target,
+    /// or `None` if immunizing all healthy individuals is not enough.
+    ///
+    /// If the report plan has a seed, the result is reproducible.
+    pub fn minimum_doses(&self) -> Option<usize> {
+        match self.report_plan.seed() {
+            Some(seed) => self.minimum_doses_with_rng(&mut rand_pcg::Pcg64::seed_from_u64(*seed)),
+            None => self.minimum_doses_with_rng(&mut rand::thread_rng()),
+        }
+    }
+
+    /// Returns the minimum number of individuals to immunize to reach the target,
+    /// or `None` if immunizing all healthy individuals is not enough, drawing randomness from `rng`.
+    ///
+    /// The seed of the report plan is ignored.
+    pub fn minimum_doses_with_rng<R: ?Sized + rand::Rng>(&self, rng: &mut R) -> Option<usize> {
+        let mut high = self.board_builder.healthy;
+        if !self.reaches_target_with_rng(high, rng) {
+            return None;
+        }
+        if self.reaches_target_with_rng(0, rng) {
+            return Some(0);
+        }
+        let mut low = 0;
+        while high - low > 1 {
+            let middle = (low + high) / 2;
+            if self.reaches_target_with_rng(middle, rng) {
+                high = middle;
+            } else {
+                low = middle;
+            }
+        }
+        Some(high)
+    }
+
+    /// Returns the estimated value of the target for every number of immunized individuals,
+    /// from zero to all healthy individuals, ie the dose-response curve.
+    ///
+    /// Each point uses the number of simulations of the report plan,
+    /// with the same realizations of randomness.
+    /// If the report plan has a seed, the result is reproducible.
+    pub fn curve(&self) -> Vec<(usize, average::Variance)> {
+        match self.report_plan.seed() {
+            Some(seed) => self.curve_with_rng(&mut rand_pcg::Pcg64::seed_from_u64(*seed)),
+            None => self.curve_with_rng(&mut rand::thread_rng()),
+        }
+    }
+
+    /// Returns the estimated value of the target for every number of immunized individuals,
+    /// from zero to all healthy individuals, drawing randomness from `rng`.
+    ///
+    /// The seed of the report plan is ignored.
+    pub fn curve_with_rng<R: ?Sized + rand::Rng>(&self, rng: &mut R) -> Vec<(usize, average::Variance)> {
+        let seed: u64 = rng.gen();
+        (0..=self.board_builder.healthy)
+            .map(|doses| {
+                let estimate = self.sample_with_rng(doses, &mut rand_pcg::Pcg64::seed_from_u64(seed)).collect();
+                (doses, estimate)
+            })
+            .collect()
+    }
+
+    /// Returns `true` if immunizing `doses` individuals reaches the target,
+    /// adding batches of realizations until it is clear, drawing randomness from `rng`.
+    fn reaches_target_with_rng<R: ?Sized + rand::Rng>(&self, doses: usize, rng: &mut R) -> bool {
+        let threshold = self.target.threshold();
+        let mut estimate = average::Variance::new();
+        loop {
+            for value in self.sample_with_rng(doses, rng) {
+                estimate.add(value);
+            }
+            let radius = 1.96 * estimate.error();
+            if estimate.is_empty() || estimate.len() as usize >= self.max_simulations {
+                return estimate.mean() >= threshold;
+            } else if estimate.mean() - radius >= threshold {
+                return true;
+            } else if estimate.mean() + radius < threshold {
+                return false;
+            }
+        }
+    }
+
+    /// Returns the value of the target in a batch of realizations,
+    /// where `doses` individuals are immunized, drawing randomness from `rng`.
+    fn sample_with_rng<R: ?Sized + rand::Rng>(&self, doses: usize, rng: &mut R) -> impl Iterator<Item = f64> + '_ {
+        let simulation = Simulation::new(self.board(doses), self.report_plan.clone());
+        simulation.run_with_rng(rng)
+            .counting_tables()
+            .iter()
+            .map(|counting_table| self.target.value(counting_table))
+            .collect::<Vec<f64>>()
+            .into_iter()
+    }
+
+    /// Returns the board with `doses` immunized individuals.
+    fn board(&self, doses: usize) -> Board {
+        let mut board = self.board_builder.clone().build();
+        for _ in 0..doses {
+            // There are no more healthy individuals to immunize
+            if board.immunize().is_err() {
+                break;
+            }
+        }
+        board
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::building::Spreading;
+
+    fn search_for(target: Target) -> ImmunizationSearch {
+        ImmunizationSearch {
+            board_builder: BoardBuilder {
+                healthy: 4,
+                infected1: 1,
+                buildings: vec![(5, 1)],
+                spreading: Spreading::Everyone,
+                ..Default::default()
+            },
+            report_plan: ReportPlan { num_simulations: 5, days: 4, ..Default::default() },
+            target,
+            max_simulations: 20,
+        }
+    }
+
+    #[test]
+    fn minimum_doses() {
+        // Everyone meets the infected individual in the first day
+        let search = search_for(Target::Healthy(3));
+        assert_eq!(search.minimum_doses_with_rng(&mut crate::tests::rng(1)), Some(3));
+        let search = search_for(Target::Healthy(5));
+        assert_eq!(search.minimum_doses_with_rng(&mut crate::tests::rng(1)), None);
+    }
+
+    #[test]
+    fn curve() {
+        let search = search_for(Target::Containment(Probability::ONE));
+        let curve: Vec<(usize, f64)> = search.curve_with_rng(&mut crate::tests::rng(1)).into_iter()
+            .map(|(doses, estimate)| (doses, estimate.mean()))
+            .collect();
+        // Unless someone is immunized, everyone is sick in the last day
+        assert_eq!(curve, vec![(0, 0.), (1, 1.), (2, 1.), (3, 1.), (4, 1.)]);
+    }
+}
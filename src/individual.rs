@@ -5,16 +5,22 @@ use serde::{Serialize, Deserialize};
 pub enum Individual {
     /// Healthy vulnerable person
     Healthy,
+    /// Infected person who can not infect others yet, see `Disease::incubation_days`
+    Exposed,
     /// Infected person in its first day
     Infected1,
-    /// Infected person in its second day
+    /// Infected person in its second day, or any day between the first and the last one
     Infected2,
     /// Infected person in its third (and last) day
     Infected3,
+    /// Infected person who never gets sick, see `Disease::asymptomatic`
+    Asymptomatic,
     /// Sick person, who goes to the hospital
     Sick,
     /// Vaccinated, and therefore immune, person
     Immune,
+    /// Sick person who died without a hospital bed, see `Hospital`
+    Dead,
 }
 
 impl Individual {
@@ -23,11 +29,21 @@ impl Individual {
     /// This is only possible if self is infected and other is healthy.
     pub fn can_infect(&self, other: &Individual) -> bool {
         match self {
-            Individual::Healthy | Individual::Sick | Individual::Immune => false,
-            Individual::Infected1 | Individual::Infected2 | Individual::Infected3 => matches!(other, Individual::Healthy),
+            Individual::Healthy | Individual::Exposed | Individual::Sick | Individual::Immune | Individual::Dead => false,
+            Individual::Infected1 | Individual::Infected2 | Individual::Infected3 | Individual::Asymptomatic => matches!(other, Individual::Healthy),
         }
     }
 
+    /// Returns true if self carries the virus, whether it can infect others or not.
+    pub fn is_infected(&self) -> bool {
+        !matches!(self, Individual::Healthy | Individual::Immune | Individual::Dead)
+    }
+
+    /// Returns true if self does not visit buildings, ie it is sick or dead.
+    pub fn stays_home(&self) -> bool {
+        matches!(self, Individual::Sick | Individual::Dead)
+    }
+
     /// Returns true if either can infect the other.
     pub fn interacts_with(&self, other: &Individual) -> bool {
         self.can_infect(other) || other.can_infect(self)
@@ -50,10 +66,29 @@ mod tests {
 	#[test_case(Individual::Infected2, Individual::Healthy, true)]
 	#[test_case(Individual::Infected3, Individual::Healthy, true)]
 	#[test_case(Individual::Infected2, Individual::Immune, false)]
+	#[test_case(Individual::Exposed, Individual::Healthy, false)]
+	#[test_case(Individual::Asymptomatic, Individual::Healthy, true)]
 	fn can_infect(i: Individual, other: Individual, expected: bool) {
 		assert_eq!(i.can_infect(&other), expected);
 	}
 
+	#[test_case(Individual::Healthy, false)]
+	#[test_case(Individual::Exposed, true)]
+	#[test_case(Individual::Sick, true)]
+	#[test_case(Individual::Immune, false)]
+	#[test_case(Individual::Dead, false)]
+	fn is_infected(i: Individual, expected: bool) {
+		assert_eq!(i.is_infected(), expected);
+	}
+
+	#[test_case(Individual::Healthy, false)]
+	#[test_case(Individual::Infected3, false)]
+	#[test_case(Individual::Sick, true)]
+	#[test_case(Individual::Dead, true)]
+	fn stays_home(i: Individual, expected: bool) {
+		assert_eq!(i.stays_home(), expected);
+	}
+
 	#[test_case(Individual::Healthy, Individual::Infected1, true)]
 	#[test_case(Individual::Infected1, Individual::Healthy, true)]
 	#[test_case(Individual::Infected2, Individual::Healthy, true)]
@@ -65,9 +100,13 @@ mod tests {
 	}
 
 	#[test_case(Individual::Healthy, Individual::Infected1, true)]
+	#[test_case(Individual::Healthy, Individual::Exposed, true)]
+	#[test_case(Individual::Exposed, Individual::Infected1, true)]
     #[test_case(Individual::Infected1, Individual::Infected2, true)]
     #[test_case(Individual::Infected2, Individual::Infected3, true)]
     #[test_case(Individual::Infected3, Individual::Sick, true)]
+    #[test_case(Individual::Infected3, Individual::Asymptomatic, true)]
+    #[test_case(Individual::Asymptomatic, Individual::Sick, true)]
     #[test_case(Individual::Sick, Individual::Immune, true)]
     #[test_case(Individual::Immune, Individual::Healthy, false)]
     fn order(i: Individual, other: Individual, expected: bool) {
@@ -11,24 +11,51 @@ pub use board::Board;
 pub use recording::Recording;
 pub use simulation::{Simulation, SimulationBuilder};
 
+/// Probabilities used in the configuration of the game.
+pub mod probability;
 /// Individuals that can be in different states of health.
 pub mod individual;
+/// Disease model, ie how individuals progress once infected.
+pub mod disease;
 /// Buildings which individuals visit.
 pub mod building;
 /// Aggregate of individuals. 
 pub mod population; 
+/// Testing of individuals and quarantine of positives.
+pub mod testing;
+/// Hospital beds for sick individuals.
+pub mod hospital;
+/// Vaccination campaigns during the game.
+pub mod vaccination;
+/// Day by day interventions on the board.
+pub mod policy;
+/// Hooks to inspect the board during a stage.
+pub mod observer;
 /// Aggregate of buildings and population.
 pub mod board;
+/// Undo and redo of changes to the board.
+pub mod history;
 /// Resources used to keep track of the state of the game.
 pub mod recording;
 /// Simulation setup and results.
 pub mod simulation;
+/// Exact computations for small boards.
+pub mod exact;
+/// Choosing which buildings to close.
+pub mod challenge;
+/// Choosing how many individuals to immunize.
+pub mod immunization;
+/// Log of what happens to each individual.
+#[cfg(feature = "event-log")]
+pub mod event_log;
 
 /// All you should need to play the game. 
 pub mod prelude {
 	pub use crate::{
         simulation::Report,
+        simulation::LastDayReport,
         simulation::report::ReportPlan, 
+        simulation::report::StopRule, 
         Board, 
         Individual, 
         Population, 
@@ -36,6 +63,7 @@ pub mod prelude {
         Simulation, 
         SimulationBuilder,
         building::Spreading,
+        disease::Disease,
     };
 }
 
@@ -49,6 +77,20 @@ pub mod errors {
         Full,
         #[error("Sick individuals are not allowed in the buildings")]
         Sick,
+        #[error("Dead individuals are not allowed in the buildings")]
+        Dead,
+        #[error("Only empty buildings can be closed")]
+        Occupied,
+        #[error("Seat {0} can not be in contact with itself")]
+        SelfContact(usize),
+        #[error("The building has {capacity} seats, so seat {seat} can not be in contact with others")]
+        ContactOutOfRange { seat: usize, capacity: usize },
+    }
+
+    #[derive(Error, Debug, PartialEq, Eq)]
+    pub enum ProbabilityError {
+        #[error("probabilities must be between zero and one")]
+        OutOfRange,
     }
 
     #[derive(Error, Debug, PartialEq, Eq)]
@@ -57,6 +99,72 @@ pub mod errors {
         NoHealthyLeft,
         #[error("There are no more immune individuals in the population")]
         NoImmuneLeft,
+        #[error("There is no building named {0}")]
+        UnknownBuilding(String),
+        #[error(transparent)]
+        Building(#[from] BuildingError),
+    }
+
+    #[derive(Error, Debug, PartialEq, Eq)]
+    pub enum DiseaseError {
+        #[error("Infected individuals must be infectious for at least one day")]
+        NoInfectiousDays,
+        #[error("Asymptomatic individuals must be infectious for at least one day")]
+        NoAsymptomaticDays,
+        #[error("Sick individuals must be sick for at least one day")]
+        NoSickDays,
+        #[error("Immune individuals must be immune for at least one day")]
+        NoImmunityDays,
+    }
+
+    #[derive(Error, Debug, PartialEq, Eq)]
+    pub enum MarkovChainError {
+        #[error("exact computations need contacts that always transmit the virus")]
+        UncertainTransmission,
+        #[error("exact computations need a disease whose compartments last one day or forever")]
+        Timers,
+        #[error("exact computations need a disease without asymptomatic individuals")]
+        Asymptomatic,
+        #[error("exact computations need a board without testing")]
+        Testing,
+        #[error("exact computations need a board without hospital")]
+        Hospital,
+        #[error("exact computations need a board without vaccination")]
+        Vaccination,
+    }
+
+    #[derive(Error, Debug, PartialEq, Eq)]
+    pub enum ChallengeError {
+        #[error("There are more than {0} sets of buildings to close within the budget")]
+        TooManySubsets(usize),
+        #[error("The board has {expected} buildings, but {found} penalties were given")]
+        PenaltiesMismatch { expected: usize, found: usize },
+        #[error(transparent)]
+        Board(#[from] BoardError),
+    }
+
+    #[derive(Error, Debug, PartialEq, Eq)]
+    pub enum HistoryError {
+        #[error("There is no previous state to undo to")]
+        NothingToUndo,
+        #[error("There is no undone state to redo")]
+        NothingToRedo,
+        #[error("There is no state of day {0} in the history")]
+        DayNotKept(usize),
+    }
+
+    #[derive(Error, Debug, PartialEq, Eq)]
+    pub enum PopulationError {
+        #[error("The population has {expected} individuals, but {found} were given")]
+        SizeMismatch { expected: usize, found: usize },
+    }
+
+    #[derive(Error, Debug, PartialEq, Eq)]
+    pub enum BoardError {
+        #[error("All buildings must have the same spreading mode")]
+        MixedSpreading,
+        #[error("Building {building} is not valid: {source}")]
+        Building { building: usize, source: BuildingError },
     }
 }
 
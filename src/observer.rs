@@ -0,0 +1,53 @@
+use crate::Board;
+use serde::{Serialize, Deserialize};
+
+/// Steps of a stage of the game after which observers are called, see `Board::advance_observed`.
+#[derive(Debug, Hash, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum Phase {
+    /// Individuals are seated in the buildings, see `Board::visit`
+    Visit,
+    /// The virus was propagated, and individuals are still in the buildings, see `Board::propagate`
+    Propagate,
+    /// Individuals returned to the population, see `Board::go_home`
+    GoHome,
+}
+
+/// Hook that inspects the board after each step of a stage of the game.
+///
+/// Observers can compute custom metrics, draw the board or check assertions,
+/// without changing the course of the game, see `Board::advance_observed`.
+/// Any closure `FnMut(Phase, &Board)` is an observer.
+///
+/// # Examples
+///
+/// Count the seats taken each day.
+/// ```
+/// # use virus_alarm::prelude::*;
+/// # use virus_alarm::observer::Phase;
+/// let mut seated = Vec::new();
+/// let mut observer = |phase: Phase, board: &Board| {
+///     if phase == Phase::Visit {
+///         let seats: usize = board.buildings().iter()
+///             .map(|building| building.people().iter().filter(|i| i.is_some()).count())
+///             .sum();
+///         seated.push(seats);
+///     }
+/// };
+/// let mut board = Board::default();
+/// board.advance_observed(&mut observer);
+/// board.advance_observed(&mut observer);
+/// assert_eq!(seated.len(), 2);
+/// ```
+pub trait Observer {
+    /// Inspects the board after `phase`.
+    fn observe(&mut self, phase: Phase, board: &Board);
+}
+
+impl<F> Observer for F
+where
+    F: FnMut(Phase, &Board),
+{
+    fn observe(&mut self, phase: Phase, board: &Board) {
+        self(phase, board)
+    }
+}
@@ -0,0 +1,54 @@
+use crate::{Recording, building::Spreading, vaccination::Vaccination};
+use serde::{Serialize, Deserialize};
+
+/// Intervention on the board, decided by a `Policy`, see `Board::apply`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Action {
+    /// Closes the buildings with this name
+    Close(String),
+    /// Opens the buildings with this name
+    Open(String),
+    /// Immunizes this number of healthy individuals
+    Immunize(usize),
+    /// Changes the spreading mode in all buildings
+    SetSpreading(Spreading),
+    /// Changes the vaccination campaign
+    SetVaccination(Option<Vaccination>),
+}
+
+/// Strategy that decides day by day how to intervene in the game.
+///
+/// Policies are called at the start of each day, with read access to the recording
+/// of the game so far, see `Simulation::run_policy`.
+/// Any closure `Fn(&Recording) -> Vec<Action>` is a policy.
+///
+/// # Examples
+///
+/// Close the school when there are more than ten infected individuals, and open it again otherwise.
+/// ```
+/// # use virus_alarm::prelude::*;
+/// # use virus_alarm::{Recording, policy::{Action, Policy}};
+/// let policy = |recording: &Recording| {
+///     let infected = recording.counting_table().diagram()[1].last().copied().unwrap_or(0);
+///     if infected > 10 {
+///         vec![Action::Close("School".to_string())]
+///     } else {
+///         vec![Action::Open("School".to_string())]
+///     }
+/// };
+/// let board = Board::default();
+/// assert_eq!(policy.actions(board.recording()), vec![Action::Open("School".to_string())]);
+/// ```
+pub trait Policy {
+    /// Returns the actions to take at the start of the day, given the recording of the game so far.
+    fn actions(&self, recording: &Recording) -> Vec<Action>;
+}
+
+impl<F> Policy for F
+where
+    F: Fn(&Recording) -> Vec<Action>,
+{
+    fn actions(&self, recording: &Recording) -> Vec<Action> {
+        self(recording)
+    }
+}
@@ -2,11 +2,40 @@ use std::collections::HashMap;
 use crate::Individual;
 use rand::seq::SliceRandom;
 use strum::IntoEnumIterator;
+use serde::{Serialize, Deserialize};
+
+/// Hidden state that an individual carries around the board.
+#[derive(Debug, Hash, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, Default)]
+pub(crate) struct Tag {
+    /// Identifier of the individual, which never changes during the game
+    pub(crate) id: usize,
+    /// Days the individual spent in its compartment, see `Disease::step`
+    pub(crate) days: usize,
+    /// Whether the individual has been infected, during the game or before it started
+    pub(crate) infected: bool,
+}
+
+impl Tag {
+    /// Returns the tag of individual `id`, who just entered its compartment.
+    pub(crate) fn new(id: usize) -> Self {
+        Tag { id, days: 0, infected: false }
+    }
+
+    /// Returns the tag of individual `id`, who is `individual` at the start.
+    pub(crate) fn of(id: usize, individual: Individual) -> Self {
+        Tag { infected: individual.is_infected() || individual == Individual::Dead, ..Tag::new(id) }
+    }
+}
 
 /// Population of the game
-#[derive(Debug, Clone, PartialEq, Eq)]
+///
+/// Each individual has an identifier, given by its position when the population is created,
+/// and remembers the number of days it spent in its compartment, see `Disease::step`,
+/// and whether it has been infected.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Population {
     population: Vec<Individual>,
+    tags: Vec<Tag>,
     counter: usize
 }
 
@@ -27,9 +56,10 @@ impl Population {
 	/// assert_eq!(population.counting(Individual::Immune), 1);
 	/// ```
 	pub fn immunize(&mut self) -> Result<&mut Self, crate::errors::ActionError> {
-		for i in self.population.iter_mut() {
+		for (i, tag) in self.population.iter_mut().zip(self.tags.iter_mut()) {
 		    if i == &mut Individual::Healthy {
 		    	*i = Individual::Immune;
+		    	tag.days = 0;
 		    	return Ok(self)
 		    }
 		}
@@ -54,9 +84,10 @@ impl Population {
 	/// assert_eq!(population.counting(Individual::Immune), 0);
 	/// ```
 	pub fn reverse_immunize(&mut self) -> Result<&mut Self, crate::errors::ActionError> {
-		for i in self.population.iter_mut() {
+		for (i, tag) in self.population.iter_mut().zip(self.tags.iter_mut()) {
 		    if i == &mut Individual::Immune {
 		    	*i = Individual::Healthy;
+		    	tag.days = 0;
 		    	return Ok(self)
 		    }
 		}
@@ -65,12 +96,42 @@ impl Population {
 
 	/// change current population for `new_population`.
 	///
+	/// Individuals keep their identifiers by position,
+	/// and start counting the days in their compartment from zero.
+	///
 	/// # Panics
 	///
 	/// If the size of the new population does not coincide with the original one.
 	pub fn update(&mut self, new_population: Vec<Individual>) {
-		assert_eq!(self.len(), new_population.len());
+		self.try_update(new_population).expect("updating the population failed!");
+	}
+
+	/// change current population for `new_population`, as in `update`.
+	///
+	/// # Errors
+	///
+	/// If the size of the new population does not coincide with the original one,
+	/// in which case nothing changes.
+	///
+	/// # Examples
+	///
+	/// ```
+	/// # use virus_alarm::prelude::*;
+	/// # use virus_alarm::errors::PopulationError;
+	/// let mut population = Population::from(vec![Individual::Healthy]);
+	/// let error = PopulationError::SizeMismatch { expected: 1, found: 2 };
+	/// assert_eq!(population.try_update(vec![Individual::Sick; 2]), Err(error));
+	/// ```
+	pub fn try_update(&mut self, new_population: Vec<Individual>) -> Result<(), crate::errors::PopulationError> {
+		if self.len() != new_population.len() {
+			return Err(crate::errors::PopulationError::SizeMismatch { expected: self.len(), found: new_population.len() });
+		}
+		for (tag, i) in self.tags.iter_mut().zip(&new_population) {
+			tag.days = 0;
+			tag.infected |= Tag::of(tag.id, *i).infected;
+		}
 		self.population = new_population;
+		Ok(())
 	}
 
 	/// Shuffles (ie reorders in a random way) the population and restarts the iterator.
@@ -87,11 +148,49 @@ impl Population {
 	/// ```
 	/// To draw a random individual from the population, 
 	pub fn shuffle<R: ?Sized + rand::Rng>(&mut self, rng: &mut R) {
-		let slice = self.population.as_mut_slice();
-		slice.shuffle(rng);
+		// Shuffling positions keeps each individual with its tag
+		let mut positions: Vec<usize> = (0..self.len()).collect();
+		positions.shuffle(rng);
+		self.population = positions.iter().map(|&position| self.population[position]).collect();
+		self.tags = positions.iter().map(|&position| self.tags[position]).collect();
 		self.counter = 0;
 	}
 
+	/// Returns the next individual, together with its tag.
+	pub(crate) fn next_with_tag(&mut self) -> Option<(Individual, Tag)> {
+		if self.counter < self.len() {
+			self.counter += 1;
+			Some((self.population[self.counter - 1], self.tags[self.counter - 1]))
+		} else {
+			None
+		}
+	}
+
+	/// Returns the individuals that were not yet returned by the iterator, 
+	/// together with their tags.
+	pub(crate) fn remaining_with_tags(&self) -> Vec<(Individual, Tag)> {
+		self.population.iter().copied()
+			.zip(self.tags.iter().copied())
+			.skip(self.counter)
+			.collect()
+	}
+
+	/// Returns an iterator over the individuals together with their identifiers,
+	/// regardless of the state of the iterator.
+	///
+	/// # Examples
+	///
+	/// Identifiers are given by the initial position of each individual.
+	/// ```
+	/// # use virus_alarm::prelude::*;
+	/// let population = Population::from(vec![Individual::Healthy, Individual::Infected1]);
+	/// let identified: Vec<(usize, Individual)> = population.iter_with_ids().collect();
+	/// assert_eq!(identified, vec![(0, Individual::Healthy), (1, Individual::Infected1)]);
+	/// ```
+	pub fn iter_with_ids(&self) -> impl Iterator<Item = (usize, Individual)> + '_ {
+		self.tags.iter().map(|tag| tag.id).zip(self.population.iter().copied())
+	}
+
 	/// Returns the size of the population
 	pub fn len(&self) -> usize {
 		self.population.len()
@@ -155,23 +254,28 @@ impl Default for Population {
 		population.push(Individual::Infected1);
 		population.push(Individual::Infected1);
 
-		Population{ population, counter: 0 }
+		Population::from(population)
 	}
 }
 
 impl From<Vec<Individual>> for Population {
-	fn from(vec: Vec<Individual>) -> Self { Population{ population: vec, counter: 0 } }
+	fn from(vec: Vec<Individual>) -> Self { 
+		let tags = vec.iter().enumerate().map(|(id, &i)| Tag::of(id, i)).collect();
+		Population{ population: vec, tags, counter: 0 } 
+	}
+}
+
+impl From<Vec<(Individual, Tag)>> for Population {
+	fn from(vec: Vec<(Individual, Tag)>) -> Self { 
+		let (population, tags) = vec.into_iter().unzip();
+		Population{ population, tags, counter: 0 } 
+	}
 }
 
 impl Iterator for Population {
 	type Item = Individual;
 	fn next(&mut self) -> Option<Self::Item> {
-		if self.counter < self.len() {
-			self.counter += 1;
-			Some(self.population[self.counter - 1])
-		} else {
-			None
-		}
+		self.next_with_tag().map(|(individual, _)| individual)
 	}
 }
 
@@ -187,9 +291,29 @@ mod tests {
 			let mut vec = vec![Individual::Healthy; 100];
 			vec[4] = Individual::Infected1;
 			vec[96] = Individual::Infected1;
-			Population::from(vec)
+			vec
 		};
-		assert_eq!(shuffled, population);
+		assert_eq!(shuffled, population.clone().collect::<Vec<Individual>>());
+		// The infected individuals were the last two
+		let infected: Vec<usize> = population.iter_with_ids()
+			.filter(|&(_, i)| i == Individual::Infected1)
+			.map(|(id, _)| id)
+			.collect();
+		assert_eq!(infected, vec![98, 99]);
+	}
+
+	#[test]
+	fn shuffle_with_tags() {
+		let tagged = vec![
+			(Individual::Healthy, Tag { id: 0, days: 0, infected: false }), 
+			(Individual::Exposed, Tag { id: 1, days: 1, infected: true }), 
+			(Individual::Infected2, Tag { id: 2, days: 2, infected: true }),
+		];
+		let mut population = Population::from(tagged.clone());
+		population.shuffle(&mut crate::tests::rng(1));
+		let mut individuals: Vec<(Individual, Tag)> = std::iter::from_fn(|| population.next_with_tag()).collect();
+		individuals.sort();
+		assert_eq!(individuals, tagged);
 	}
 
 	#[test]
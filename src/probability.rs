@@ -0,0 +1,119 @@
+use core::convert::TryFrom;
+use crate::errors::ProbabilityError;
+use serde::{Serialize, Deserialize};
+
+/// Probability of an event, ie a number between zero and one.
+///
+/// # Remarks
+///
+/// Contrary to `f64`, it implements `Eq`, `Ord` and `Hash`,
+/// so that it can be part of any configuration.
+///
+/// # Examples
+///
+/// ```
+/// # use core::convert::TryFrom;
+/// # use virus_alarm::probability::Probability;
+/// let probability = Probability::try_from(0.5).unwrap();
+/// assert_eq!(probability.value(), 0.5);
+/// assert!(Probability::try_from(1.5).is_err());
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "f64", into = "f64")]
+pub struct Probability(f64);
+
+impl Probability {
+    /// The probability of an impossible event.
+    pub const ZERO: Probability = Probability(0.);
+    /// The probability of a certain event.
+    pub const ONE: Probability = Probability(1.);
+
+    /// Returns the probability as a number.
+    pub fn value(&self) -> f64 {
+        self.0
+    }
+
+    /// Returns `true` if the event always happens.
+    pub fn is_certain(&self) -> bool {
+        self.0 >= 1.
+    }
+
+    /// Returns `true` if the event happens in a random trial.
+    ///
+    /// # Remarks
+    ///
+    /// No randomness is drawn from `rng` if the event is certain or impossible.
+    pub fn sample<R: ?Sized + rand::Rng>(&self, rng: &mut R) -> bool {
+        self.is_certain() || (self.0 > 0. && rng.gen_bool(self.0))
+    }
+}
+
+impl TryFrom<f64> for Probability {
+    type Error = ProbabilityError;
+    fn try_from(value: f64) -> Result<Self, Self::Error> {
+        if value == 0. {
+            // Avoids a negative zero, which would hash differently
+            Ok(Probability::ZERO)
+        } else if (0. ..=1.).contains(&value) {
+            Ok(Probability(value))
+        } else {
+            Err(ProbabilityError::OutOfRange)
+        }
+    }
+}
+
+impl From<Probability> for f64 {
+    fn from(probability: Probability) -> f64 {
+        probability.0
+    }
+}
+
+// Probabilities are never NaN, so the order is total.
+impl Eq for Probability {}
+
+impl Ord for Probability {
+    fn cmp(&self, other: &Self) -> core::cmp::Ordering {
+        self.0.partial_cmp(&other.0).expect("probabilities are never NaN")
+    }
+}
+
+impl PartialOrd for Probability {
+    fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl core::hash::Hash for Probability {
+    fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+        self.0.to_bits().hash(state);
+    }
+}
+
+impl core::fmt::Display for Probability {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    #[test_case(0., true)]
+    #[test_case(0.3, true)]
+    #[test_case(1., true)]
+    #[test_case(-0.1, false)]
+    #[test_case(1.1, false)]
+    #[test_case(f64::NAN, false)]
+    fn try_from(value: f64, expected: bool) {
+        assert_eq!(Probability::try_from(value).is_ok(), expected);
+    }
+
+    #[test]
+    fn sample() {
+        let mut rng = crate::tests::rng(1);
+        assert!((0..100).all(|_| Probability::ONE.sample(&mut rng)));
+        assert!((0..100).all(|_| !Probability::ZERO.sample(&mut rng)));
+    }
+}
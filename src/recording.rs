@@ -2,13 +2,18 @@ use std::collections::HashMap;
 use crate::{Building, Individual, Population, prelude::Spreading};
 use getset::{Getters, MutGetters};
 use strum::IntoEnumIterator;
+use serde::{Serialize, Deserialize};
 
 
 mod counting_table;
 pub use counting_table::*;
+mod infection_tree;
+pub use infection_tree::*;
+mod attendance;
+pub use attendance::*;
 
 /// Represents the state of the game and have high level commands.
-#[derive(Debug, Clone, PartialEq, Eq, Getters, MutGetters)]
+#[derive(Debug, Clone, PartialEq, Eq, Getters, MutGetters, Serialize, Deserialize)]
 pub struct Recording {
 	/// Returns a "table" with the counting of individual types per day.
 	///
@@ -20,11 +25,53 @@ pub struct Recording {
     #[getset(get = "pub", get_mut)]
     timeline: usize, 
     /// Returns a table with the counting of penalty for each building per day.  
+    ///
+    /// A building is penalized every day it is closed, see `Building::penalty`.
     #[getset(get = "pub", get_mut)]
     penalty: Vec<(Building, Vec<usize>)>,
     /// Returns the score obtained per day.  
+    ///
+    /// The score of a day is the number of healthy or immune individuals, 
+    /// minus the penalties of the buildings closed that day.
+    /// The first value is always zero, since no day has been played.
     #[getset(get = "pub", get_mut)]
     daily_score: Vec<isize>,
+    /// Returns the number of tests used per day, see `Board::test`.
+    #[getset(get = "pub", get_mut)]
+    tests: Vec<usize>,
+    /// Returns the number of vaccine doses given per day, see `Board::vaccinate`.
+    #[getset(get = "pub", get_mut)]
+    doses: Vec<usize>,
+    /// Returns who infected whom during the game, see `Board::infections`.
+    #[getset(get = "pub", get_mut)]
+    infection_tree: InfectionTree,
+    /// Returns the counting per day of visitors and infections in each building, 
+    /// in the order of the buildings of the board.
+    #[getset(get = "pub", get_mut)]
+    attendance: Vec<Attendance>,
+}
+
+/// Summary of a stage of the game, as registered by `Recording::register`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Stage<'a> {
+	/// Number of individuals of each type, including the `quarantined` ones
+	pub counting: HashMap<Individual, usize>,
+	/// Number of individuals in quarantine
+	pub quarantined: usize,
+	/// Number of sick individuals without a hospital bed
+	pub overload: usize,
+	/// Flows that happened during the stage
+	pub flows: &'a HashMap<Flow, usize>,
+	/// Infections that happened during the stage
+	pub infections: &'a [Infection],
+	/// Number of visitors and of infected visitors of each building during the stage
+	pub visitors: &'a [(usize, usize)],
+	/// Number of tests used during the stage
+	pub tests: usize,
+	/// Number of vaccine doses given during the stage
+	pub doses: usize,
+	/// Buildings after the stage
+	pub buildings: &'a [Building],
 }
 
 impl Recording {
@@ -35,9 +82,13 @@ impl Recording {
 	pub fn new(population: Population, buildings: Vec<Building>) -> Self { 
 		let mut recording = Recording::default();
 		for building in buildings {
+			recording.attendance.push(Attendance::new(building.name()));
 			recording.penalty.push((building, vec![0]));
 		}
 		recording.counting_table = population.counting_all().iter().map(|(&i, &val)| (i, vec![val])).collect();
+		*recording.counting_table.flows_mut() = Flow::iter().map(|flow| (flow, vec![0])).collect();
+		*recording.counting_table.quarantined_mut() = vec![0];
+		*recording.counting_table.overload_mut() = vec![0];
 
 		recording
 	}
@@ -93,7 +144,31 @@ impl Recording {
 		self
 	}
 
-	/// Immunize one person in the population. 
+	/// Changes the penalty of the buildings with the given name.
+	pub(crate) fn set_penalty(&mut self, name: &str, new_penalty: usize) -> &mut Self {
+		for (building, _) in self.penalty_mut().iter_mut() {
+			if building.name() == name {
+				building.set_penalty(new_penalty);
+			}
+		}
+		self
+	}
+
+	/// Returns the total score over all days, see `daily_score`.
+	///
+	/// # Examples
+	///
+	/// No day has been played.
+	/// ```
+	/// # use virus_alarm::prelude::*;
+	/// let board = Board::default();
+	/// assert_eq!(board.recording().score(), 0);
+	/// ```
+	pub fn score(&self) -> isize {
+		self.daily_score().iter().sum()
+	}
+
+	/// Immunize one person in the initial day, see `Board::immunize`. 
 	/// 
 	/// # Errors
 	///
@@ -112,7 +187,7 @@ impl Recording {
 		
 	}
 
-	/// Reverse one individual from immune to healthy in the population. 
+	/// Reverse one individual from immune to healthy in the initial day, see `Board::reverse_immunize`. 
 	/// 
 	/// # Errors
 	///
@@ -139,36 +214,69 @@ impl Recording {
 		self.counting_table().diagram()
 	}
 
-	/// Main functions that registers newly infected individuals
-	///
-	/// # Panics
-	///
-	/// If the number of newly infected is larger than the number of healthy individuals available
-	pub(crate) fn register(&mut self, newly_infected: usize, _buildings: &[Building]) -> &mut Self {
-		self.register_counting_table(newly_infected);
-		// self.register_penalty(buildings);
-		// self.register_daily_score(buildings);
+	/// Main functions that registers the state of the population after a day, 
+	/// together with the flows that happened and the resources used during the day.
+	pub(crate) fn register(&mut self, stage: Stage) -> &mut Self {
+		self.register_counting_table(stage.counting, stage.quarantined, stage.overload);
+		self.register_flows(stage.flows);
+		self.infection_tree.extend(stage.infections.iter().copied());
+		self.register_attendance(stage.visitors, stage.infections);
+		self.tests.push(stage.tests);
+		self.doses.push(stage.doses);
+		self.register_penalty(stage.buildings);
+		self.register_daily_score();
 		self.increment_time();
 		self
 	}
 
-	fn register_counting_table(&mut self, newly_infected: usize) {
-	 	let last_values = self.last_day_individuals();
+	/// Counts the individuals of each type as a new day.
+	fn register_counting_table(&mut self, counting: HashMap<Individual, usize>, quarantined: usize, overload: usize) {
+		let days = self.counting_table().days();
+		for (individual, count) in counting {
+			let row = self.counting_table_mut().inner_mut().entry(individual).or_insert_with(|| vec![0; days]);
+			row.push(count);
+		}
+		let row = self.counting_table_mut().quarantined_mut();
+		row.resize(days, 0);
+		row.push(quarantined);
+		let row = self.counting_table_mut().overload_mut();
+		row.resize(days, 0);
+		row.push(overload);
+	}
 
-		let counting_table = self.counting_table_mut();
-	 	counting_table.inner_mut().entry(Individual::Healthy).and_modify(|v| v.push(last_values[&Individual::Healthy] - newly_infected));
-	 	counting_table.inner_mut().entry(Individual::Infected1).and_modify(|v| v.push(newly_infected));
-	 	counting_table.inner_mut().entry(Individual::Infected2).and_modify(|v| v.push(last_values[&Individual::Infected1]));
-	 	counting_table.inner_mut().entry(Individual::Infected3).and_modify(|v| v.push(last_values[&Individual::Infected2]));
-	 	counting_table.inner_mut().entry(Individual::Sick).and_modify(|v| v.push(last_values[&Individual::Infected3] + last_values[&Individual::Sick]));
-	 	counting_table.inner_mut().entry(Individual::Immune).and_modify(|v| v.push(last_values[&Individual::Immune]));
+	/// Registers the penalty of each building in a new day, which is only paid if it is closed.
+	fn register_penalty(&mut self, buildings: &[Building]) {
+		for ((_, row), building) in self.penalty_mut().iter_mut().zip(buildings) {
+			row.push(if building.is_open() { 0 } else { *building.penalty() });
+		}
 	}
 
-	/// # Panics
-	///
-	/// If the counting table is empty.
-	fn last_day_individuals(&self) -> HashMap<Individual, usize> {
-		self.counting_table().last_day()
+	/// Registers the visitors and infections of each building in a new day.
+	fn register_attendance(&mut self, visitors: &[(usize, usize)], infections: &[Infection]) {
+		for (index, attendance) in self.attendance.iter_mut().enumerate() {
+			let (visitors, infected_visitors) = visitors.get(index).copied().unwrap_or((0, 0));
+			let infections = infections.iter().filter(|infection| infection.building == index).count();
+			attendance.push(visitors, infected_visitors, infections);
+		}
+	}
+
+	/// Registers the score of a new day, from its counting of individuals and penalties.
+	fn register_daily_score(&mut self) {
+		let day = self.counting_table().days() - 1;
+		let health = self.counting_table().get(&Individual::Healthy, day) + self.counting_table().get(&Individual::Immune, day);
+		let penalty: usize = self.penalty().iter()
+			.filter_map(|(_, row)| row.last())
+			.sum();
+		self.daily_score.push(health as isize - penalty as isize);
+	}
+
+	/// Counts the flows of a new day, which were counted after the counting of individuals.
+	fn register_flows(&mut self, flows: &HashMap<Flow, usize>) {
+		let days = self.counting_table().days();
+		for flow in Flow::iter() {
+			let row = self.counting_table_mut().flows_mut().entry(flow).or_insert_with(|| vec![0; days - 1]);
+			row.push(flows.get(&flow).copied().unwrap_or(0));
+		}
 	}
 }
 
@@ -177,13 +285,21 @@ impl Recording {
 impl Default for Recording {
 	// add code here
 	fn default() -> Self { 
-		let counting_table = Individual::iter().map(|i| (i, vec![0])).collect();
+		let mut counting_table: CountingTable = Individual::iter().map(|i| (i, vec![0])).collect();
+		*counting_table.flows_mut() = Flow::iter().map(|flow| (flow, vec![0])).collect();
+		*counting_table.quarantined_mut() = vec![0];
+		*counting_table.overload_mut() = vec![0];
 		// let diagram = [vec![0], vec![0], vec![0]];
 		let timeline = 0;
 		let penalty = Vec::new();
 		let daily_score = vec![0];
+		let tests = vec![0];
+		let doses = vec![0];
+
+		let infection_tree = InfectionTree::default();
+		let attendance = Vec::new();
 
-		Recording { counting_table, timeline, penalty, daily_score }
+		Recording { counting_table, timeline, penalty, daily_score, tests, doses, infection_tree, attendance }
 	}
 }
 
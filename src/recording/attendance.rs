@@ -0,0 +1,73 @@
+use getset::Getters;
+use serde::{Serialize, Deserialize};
+
+/// Counting per day of what happened inside a building.
+///
+/// As in `CountingTable`, the first value corresponds to the initial state,
+/// so it is always zero.
+///
+/// # Examples
+///
+/// A building that is yet to be visited.
+/// ```
+/// # use virus_alarm::recording::Attendance;
+/// let attendance = Attendance::new("School");
+/// assert_eq!(attendance.name(), "School");
+/// assert_eq!(attendance.infections(), &vec![0]);
+/// assert_eq!(attendance.total_infections(), 0);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Getters, Serialize, Deserialize)]
+pub struct Attendance {
+    /// Returns the name of the building.
+    #[getset(get = "pub")]
+    name: String,
+    /// Returns the number of individuals that visited the building per day.
+    #[getset(get = "pub")]
+    visitors: Vec<usize>,
+    /// Returns the number of visitors that carried the virus per day, see `Individual::is_infected`.
+    #[getset(get = "pub")]
+    infected_visitors: Vec<usize>,
+    /// Returns the number of visitors that got infected in the building per day.
+    #[getset(get = "pub")]
+    infections: Vec<usize>,
+}
+
+impl Attendance {
+    /// Creates the attendance of a building with the given name, before any day is played.
+    pub fn new<S: core::fmt::Display>(name: S) -> Self {
+        Attendance {
+            name: name.to_string(),
+            visitors: vec![0],
+            infected_visitors: vec![0],
+            infections: vec![0],
+        }
+    }
+
+    /// Returns the number of days recorded, including the initial state.
+    pub fn days(&self) -> usize {
+        self.visitors.len()
+    }
+
+    /// Returns the number of visits over all days.
+    pub fn total_visitors(&self) -> usize {
+        self.visitors.iter().sum()
+    }
+
+    /// Returns the number of visits of individuals carrying the virus over all days.
+    pub fn total_infected_visitors(&self) -> usize {
+        self.infected_visitors.iter().sum()
+    }
+
+    /// Returns the number of infections that happened in the building over all days.
+    pub fn total_infections(&self) -> usize {
+        self.infections.iter().sum()
+    }
+
+    /// Counts a new day.
+    pub(crate) fn push(&mut self, visitors: usize, infected_visitors: usize, infections: usize) -> &mut Self {
+        self.visitors.push(visitors);
+        self.infected_visitors.push(infected_visitors);
+        self.infections.push(infections);
+        self
+    }
+}
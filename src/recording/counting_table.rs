@@ -3,6 +3,26 @@ use crate::{Individual};
 use getset::{Getters, MutGetters};
 use strum::IntoEnumIterator;
 use ndarray::Array2;
+use serde::{Serialize, Deserialize};
+
+/// Transitions between compartments that are counted per day, 
+/// since they can not be recovered from the counting of individuals.
+#[derive(strum_macros::EnumIter, Hash, Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum Flow {
+    /// Healthy individuals that got infected
+    Infection,
+    /// Sick individuals that recovered, see `Disease::sick_days`
+    Recovery,
+    /// Immune individuals that became healthy again, see `Disease::immunity_days`
+    Waning,
+}
+
+impl core::fmt::Display for Flow {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
 /// Represents the state of the game and have high level commands.
 ///
 /// # Examples
@@ -13,23 +33,29 @@ use ndarray::Array2;
 /// # use virus_alarm::recording::CountingTable;
 /// let counting_table = CountingTable::from(vec![
 ///     (Individual::Healthy, vec![98, 97]),
+///     (Individual::Exposed, vec![0, 0]),
 ///     (Individual::Infected1, vec![2, 1]),
 ///     (Individual::Infected2, vec![0, 2]),
 ///     (Individual::Infected3, vec![0, 0]),
+///     (Individual::Asymptomatic, vec![0, 0]),
 ///     (Individual::Sick, vec![0, 0]),
 ///     (Individual::Immune, vec![0, 0]),
+///     (Individual::Dead, vec![0, 0]),
 /// ]);
 /// assert_eq!(counting_table.to_string(), String::from("\
 ///     Individual\\Day 0  1  \n\
 ///     Healthy        98 97 \n\
+///     Exposed        0  0  \n\
 ///     Infected1      2  1  \n\
 ///     Infected2      0  2  \n\
 ///     Infected3      0  0  \n\
+///     Asymptomatic   0  0  \n\
 ///     Sick           0  0  \n\
 ///     Immune         0  0  \n\
+///     Dead           0  0  \n\
 /// "));
 /// ```
-#[derive(Debug, Clone, PartialEq, Eq, Getters, MutGetters, Default)]
+#[derive(Debug, Clone, PartialEq, Eq, Getters, MutGetters, Serialize, Deserialize, Default)]
 pub struct CountingTable {
     /// Returns a "table" with the counting of individual types per day.
     ///
@@ -37,6 +63,19 @@ pub struct CountingTable {
     /// the vector of numbers represents the count for each of the days that have passed.
     #[getset(get = "pub", get_mut = "pub")]
     inner: HashMap<Individual, Vec<usize>>,
+    /// Returns a "table" with the counting of flows per day, see `Flow`.
+    ///
+    /// Flows are counted from the second day on, so the first value is always zero.
+    #[getset(get = "pub", get_mut = "pub")]
+    flows: HashMap<Flow, Vec<usize>>,
+    /// Returns the number of individuals in quarantine per day, see `Board::test`.
+    ///
+    /// Quarantined individuals are also counted in the row of their individual type.
+    #[getset(get = "pub", get_mut = "pub")]
+    quarantined: Vec<usize>,
+    /// Returns the number of sick individuals without a hospital bed per day, see `Hospital`.
+    #[getset(get = "pub", get_mut = "pub")]
+    overload: Vec<usize>,
 }
 
 impl CountingTable {
@@ -49,7 +88,21 @@ impl CountingTable {
     /// CountingTable::new();
     /// ```
     pub fn new() -> Self {
-        Self { inner: HashMap::new() }
+        Self { inner: HashMap::new(), flows: HashMap::new(), quarantined: Vec::new(), overload: Vec::new() }
+    }
+
+    /// Returns `true` if the hospital was overloaded in any day, see `Hospital`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use virus_alarm::recording::CountingTable;
+    /// let mut counting_table = CountingTable::new();
+    /// *counting_table.overload_mut() = vec![0, 2, 0];
+    /// assert!(counting_table.is_overloaded());
+    /// ```
+    pub fn is_overloaded(&self) -> bool {
+        self.overload.iter().any(|&overload| overload > 0)
     }
 
     /// Returns `true` if the outbreak of the virus is contained in the last day.
@@ -93,8 +146,9 @@ impl CountingTable {
     /// ```
     pub fn is_contained(&self) -> bool {
         let last_day = self.last_day();
-        (last_day[&Individual::Healthy] + last_day[&Individual::Immune] > 0) 
-            && (last_day[&Individual::Infected1] + last_day[&Individual::Infected2] + last_day[&Individual::Infected3] == 0)
+        let count = |i: Individual| last_day.get(&i).copied().unwrap_or(0);
+        (count(Individual::Healthy) + count(Individual::Immune) > 0) 
+            && (count(Individual::Exposed) + count(Individual::Infected1) + count(Individual::Infected2) + count(Individual::Infected3) + count(Individual::Asymptomatic) == 0)
     }
 
 
@@ -106,6 +160,36 @@ impl CountingTable {
         }
     }
 
+    /// Returns the count of `individual` in `day`, which is zero if it is not counted in the table.
+    ///
+    /// # Panics
+    ///
+    /// If `day` was not counted.
+    pub fn get(&self, individual: &Individual, day: usize) -> usize {
+        match self.inner().get(individual) {
+            Some(v) => v[day],
+            None => {
+                assert!(day < self.days(), "day {} was not counted", day);
+                0
+            },
+        }
+    }
+
+    /// Returns the count of `flow` in `day`, which is zero if it is not counted in the table.
+    ///
+    /// # Panics
+    ///
+    /// If `day` was not counted.
+    pub fn flow(&self, flow: &Flow, day: usize) -> usize {
+        match self.flows().get(flow) {
+            Some(v) => v[day],
+            None => {
+                assert!(day < self.days(), "day {} was not counted", day);
+                0
+            },
+        }
+    }
+
     /// Returns the information about the last day in the counting table.
     ///
     /// # Panics
@@ -135,14 +219,16 @@ impl CountingTable {
     ///
     /// The information provided in this table is the total number of 
     /// infected, sick and healthy individuals respectively for each day that has been recorded.
+    ///
+    /// Exposed and asymptomatic individuals count as infected.
     pub fn diagram(&self) -> [Vec<usize>; 3] {
         let healthy = &self.inner()[&Individual::Healthy];
-        let infected = self.inner()[&Individual::Infected1].iter()
-            .zip(
-            self.inner()[&Individual::Infected2].iter()
-            ).zip(
-            self.inner()[&Individual::Infected3].iter()
-            ).map(|((inf1, inf2), inf3)| inf1 + inf2 + inf3)
+        let infected = (0..self.days())
+            .map(|day| {
+                [Individual::Exposed, Individual::Infected1, Individual::Infected2, Individual::Infected3, Individual::Asymptomatic].iter()
+                    .map(|i| self.get(i, day))
+                    .sum()
+            })
             .collect();
         let sick = &self.inner()[&Individual::Sick];
         [healthy.to_vec(), infected, sick.to_vec()]
@@ -160,7 +246,28 @@ impl Into<Vec<Vec<String>>> for CountingTable {
         for i in Individual::iter() {
             table.push({
                 let mut row = vec![i.to_string()];
-                row.extend((0..self.days()).map(|day| self.inner()[&i][day].to_string()));
+                row.extend((0..self.days()).map(|day| self.get(&i, day).to_string()));
+                row
+                });
+        }
+        if !self.quarantined().is_empty() {
+            table.push({
+                let mut row = vec!["Quarantined".to_string()];
+                row.extend(self.quarantined().iter().map(|value| value.to_string()));
+                row
+                });
+        }
+        if !self.overload().is_empty() {
+            table.push({
+                let mut row = vec!["Overload".to_string()];
+                row.extend(self.overload().iter().map(|value| value.to_string()));
+                row
+                });
+        }
+        for flow in Flow::iter().filter(|flow| self.flows().contains_key(flow)) {
+            table.push({
+                let mut row = vec![flow.to_string()];
+                row.extend((0..self.days()).map(|day| self.flow(&flow, day).to_string()));
                 row
                 });
         }
@@ -189,7 +296,7 @@ where
     T: IntoIterator<Item = (Individual, Vec<usize>)>,
 {
     fn from(iter: T) -> Self {
-        CountingTable{ inner: iter.into_iter().collect() }
+        CountingTable{ inner: iter.into_iter().collect(), flows: HashMap::new(), quarantined: Vec::new(), overload: Vec::new() }
     }
 }
 
@@ -198,7 +305,7 @@ impl core::iter::FromIterator<(Individual, Vec<usize>)> for CountingTable {
     where 
         T: std::iter::IntoIterator<Item = (Individual, Vec<usize>)>, 
     {
-        CountingTable{ inner: iter.into_iter().collect() }
+        CountingTable{ inner: iter.into_iter().collect(), flows: HashMap::new(), quarantined: Vec::new(), overload: Vec::new() }
     }
 }
 
@@ -211,11 +318,11 @@ impl From<CountingTable> for Array2<usize> {
 
 impl From<&CountingTable> for Array2<usize> {
     fn from(counting_table: &CountingTable) -> Array2<usize> {
-        let mut array = Array2::from_elem((6, counting_table.days()), 0);
         let individual_variants: Vec<Individual> = Individual::iter().collect();
-        for counter in 0..individual_variants.len() {
+        let mut array = Array2::from_elem((individual_variants.len(), counting_table.days()), 0);
+        for (counter, individual) in individual_variants.iter().enumerate() {
             for day in 0..counting_table.days() {
-                array[[counter, day]] = counting_table.inner()[&individual_variants[counter]][day];
+                array[[counter, day]] = counting_table.get(individual, day);
             }
         }
         array
@@ -224,7 +331,9 @@ impl From<&CountingTable> for Array2<usize> {
 
 impl From<&CountingTable> for Vec<(String, Vec<usize>)> {
     fn from(counting_table: &CountingTable) -> Vec<(String, Vec<usize>)> {
-        Individual::iter().map(|i| (i.to_string(), counting_table.inner()[&i].clone())).collect()
+        Individual::iter()
+            .map(|i| (i.to_string(), (0..counting_table.days()).map(|day| counting_table.get(&i, day)).collect()))
+            .collect()
     }
 }
 
@@ -240,15 +349,16 @@ mod tests {
         let population = Population::from(vec![Individual::Healthy, Individual::Infected1, Individual::Infected3]);
         let buildings = Vec::new();
         let mut recording = Recording::new(population, buildings);
-        let last_day = recording.last_day_individuals();
+        let last_day = recording.counting_table().last_day();
         assert_eq!(last_day[&Individual::Healthy], 1);
         assert_eq!(last_day[&Individual::Infected1], 1);
         assert_eq!(last_day[&Individual::Infected2], 0);
         assert_eq!(last_day[&Individual::Infected3], 1);
         assert_eq!(last_day[&Individual::Sick], 0);
         assert_eq!(last_day[&Individual::Immune], 0);
-        recording.register_counting_table(1);
-        let last_day = recording.last_day_individuals();
+        let population = Population::from(vec![Individual::Infected1, Individual::Infected2, Individual::Sick]);
+        recording.register_counting_table(population.counting_all(), 0, 0);
+        let last_day = recording.counting_table().last_day();
         assert_eq!(last_day[&Individual::Healthy], 0);
         assert_eq!(last_day[&Individual::Infected1], 1);
         assert_eq!(last_day[&Individual::Infected2], 1);
@@ -258,21 +368,16 @@ mod tests {
     }
 
     #[test]
-    #[should_panic]
     fn register_counting_table2() {
         let population = Population::from(vec![Individual::Healthy]);
         let buildings = Vec::new();
         let mut recording = Recording::new(population, buildings);
+        *recording.counting_table_mut() = CountingTable::from(vec![(Individual::Healthy, vec![1])]);
+        assert_eq!(recording.counting_table().get(&Individual::Exposed, 0), 0);
 
-        let last_day = recording.last_day_individuals();
-        assert_eq!(last_day[&Individual::Healthy], 1);
-        assert_eq!(last_day[&Individual::Infected1], 0);
-        assert_eq!(last_day[&Individual::Infected2], 0);
-        assert_eq!(last_day[&Individual::Infected3], 0);
-        assert_eq!(last_day[&Individual::Sick], 0);
-        assert_eq!(last_day[&Individual::Immune], 0);
-
-        recording.register_counting_table(2);
+        recording.register_counting_table(Population::from(vec![Individual::Exposed]).counting_all(), 0, 0);
+        assert_eq!(recording.counting_table().inner()[&Individual::Healthy], vec![1, 0]);
+        assert_eq!(recording.counting_table().inner()[&Individual::Exposed], vec![0, 1]);
     }
 
     #[test]
@@ -281,35 +386,61 @@ mod tests {
         let counting_table: CountingTable = Individual::iter().map(|i| (i, vec![0])).collect();
         let writer = counting_table.write_on(writer)?;
         let data = String::from_utf8(writer.into_inner().unwrap()).unwrap();
-        assert_eq!(data, String::from("Individual\\Day,0\nHealthy,0\nInfected1,0\nInfected2,0\nInfected3,0\nSick,0\nImmune,0\n"));
+        assert_eq!(data, String::from("Individual\\Day,0\nHealthy,0\nExposed,0\nInfected1,0\nInfected2,0\nInfected3,0\nAsymptomatic,0\nSick,0\nImmune,0\nDead,0\n"));
         Ok(())
     }
 
     #[test]
     fn array2() {
         let counting_table: CountingTable = Individual::iter().map(|i| (i, vec![0])).collect();
-        let expected = array![[0], [0], [0], [0], [0], [0]];
+        let expected = array![[0], [0], [0], [0], [0], [0], [0], [0], [0]];
         assert_eq!(Array2::from(&counting_table), expected);
     }
 
     #[test]
     fn diagram() {
         let counting_table: CountingTable = Individual::iter().map(|i| (i, vec![1, 2])).collect();
-        let expected = [vec![1, 2], vec![3, 6], vec![1, 2]];
+        let expected = [vec![1, 2], vec![5, 10], vec![1, 2]];
         assert_eq!(counting_table.diagram(), expected);
     }
 
+    #[test]
+    fn display_flows_quarantined_and_overload() {
+        let mut counting_table: CountingTable = vec![(Individual::Healthy, vec![2, 1])].into_iter().collect();
+        counting_table.flows_mut().insert(Flow::Infection, vec![0, 1]);
+        *counting_table.quarantined_mut() = vec![0, 1];
+        *counting_table.overload_mut() = vec![0, 0];
+        let expected = String::from("\
+            Individual\\Day 0  1  \n\
+            Healthy        2  1  \n\
+            Exposed        0  0  \n\
+            Infected1      0  0  \n\
+            Infected2      0  0  \n\
+            Infected3      0  0  \n\
+            Asymptomatic   0  0  \n\
+            Sick           0  0  \n\
+            Immune         0  0  \n\
+            Dead           0  0  \n\
+            Quarantined    0  1  \n\
+            Overload       0  0  \n\
+            Infection      0  1  \n");
+        assert_eq!(format!("{}", counting_table), expected);
+    }
+
     #[test]
     fn display() {
         let counting_table: CountingTable = Individual::iter().map(|i| (i, vec![0])).collect();
         let expected = String::from("\
             Individual\\Day 0  \n\
             Healthy        0  \n\
+            Exposed        0  \n\
             Infected1      0  \n\
             Infected2      0  \n\
             Infected3      0  \n\
+            Asymptomatic   0  \n\
             Sick           0  \n\
-            Immune         0  \n");
+            Immune         0  \n\
+            Dead           0  \n");
         println!("{}", counting_table);
         assert_eq!(format!("{}", counting_table), expected);
     }
@@ -0,0 +1,129 @@
+use getset::Getters;
+use serde::{Serialize, Deserialize};
+
+/// Transmission of the virus from an infected individual to a healthy one.
+///
+/// Individuals are referred to by their identifiers, see `Population::iter_with_ids`.
+#[derive(Debug, Hash, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct Infection {
+    /// Identifier of the individual who transmitted the virus
+    pub infector: usize,
+    /// Identifier of the individual who got infected
+    pub infectee: usize,
+    /// Index of the building where the infection happened, in the order of the buildings of the board
+    pub building: usize,
+    /// Seat of the infectee in the building, where seats are numbered row by row
+    pub seat: usize,
+    /// Day in which the infection happened, ie the day of the counting table where the infectee is first counted as infected
+    pub day: usize,
+}
+
+/// Record of who infected whom during the game.
+///
+/// Individuals that were infected from the start have no infector.
+///
+/// # Examples
+///
+/// Individual 0 infected individual 1, who infected individual 2.
+/// ```
+/// # use virus_alarm::recording::{Infection, InfectionTree};
+/// let tree: InfectionTree = vec![
+///     Infection { infector: 0, infectee: 1, building: 0, seat: 1, day: 1 },
+///     Infection { infector: 1, infectee: 2, building: 0, seat: 2, day: 3 },
+/// ].into_iter().collect();
+/// assert_eq!(tree.infector_of(2), Some(1));
+/// assert_eq!(tree.infectees_of(0), vec![1]);
+/// assert_eq!(tree.chain(2), vec![0, 1, 2]);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Getters, Serialize, Deserialize, Default)]
+pub struct InfectionTree {
+    /// Returns all infections, in the order they happened.
+    #[getset(get = "pub")]
+    infections: Vec<Infection>,
+}
+
+impl InfectionTree {
+    /// Returns the identifier of the individual who last infected `id`,
+    /// or `None` if `id` was never infected during the game.
+    pub fn infector_of(&self, id: usize) -> Option<usize> {
+        self.last_infection_of(id, usize::MAX).map(|infection| infection.infector)
+    }
+
+    /// Returns the identifiers of the individuals infected by `id`, in the order they were infected.
+    pub fn infectees_of(&self, id: usize) -> Vec<usize> {
+        self.infections.iter()
+            .filter(|infection| infection.infector == id)
+            .map(|infection| infection.infectee)
+            .collect()
+    }
+
+    /// Returns the transmission chain that ends in `id`,
+    /// ie the identifiers of its infector, the infector of its infector, and so on,
+    /// from the first one to `id` itself.
+    ///
+    /// If an individual was infected more than once,
+    /// the chain follows its last infection before passing the virus on.
+    pub fn chain(&self, id: usize) -> Vec<usize> {
+        let mut chain = vec![id];
+        let mut current = (id, usize::MAX);
+        while let Some(infection) = self.last_infection_of(current.0, current.1) {
+            chain.push(infection.infector);
+            current = (infection.infector, infection.day);
+        }
+        chain.reverse();
+        chain
+    }
+
+    /// Returns the number of infections recorded.
+    pub fn len(&self) -> usize {
+        self.infections.len()
+    }
+
+    /// Returns `true` if no infection was recorded.
+    pub fn is_empty(&self) -> bool {
+        self.infections.is_empty()
+    }
+
+    /// Returns the last infection of `id` that happened strictly before `day`.
+    fn last_infection_of(&self, id: usize, day: usize) -> Option<&Infection> {
+        self.infections.iter()
+            .rev()
+            .find(|infection| infection.infectee == id && infection.day < day)
+    }
+}
+
+impl Extend<Infection> for InfectionTree {
+    fn extend<T: IntoIterator<Item = Infection>>(&mut self, iter: T) {
+        self.infections.extend(iter)
+    }
+}
+
+impl core::iter::FromIterator<Infection> for InfectionTree {
+    fn from_iter<T: IntoIterator<Item = Infection>>(iter: T) -> Self {
+        InfectionTree { infections: iter.into_iter().collect() }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn infection(infector: usize, infectee: usize, day: usize) -> Infection {
+        Infection { infector, infectee, building: 0, seat: 0, day }
+    }
+
+    #[test]
+    fn chain_with_reinfection() {
+        // Individual 1 is infected twice, and passes the virus on after the first time
+        let tree: InfectionTree = vec![
+            infection(0, 1, 1),
+            infection(1, 2, 2),
+            infection(3, 1, 5),
+        ].into_iter().collect();
+        assert_eq!(tree.infector_of(1), Some(3));
+        assert_eq!(tree.chain(2), vec![0, 1, 2]);
+        assert_eq!(tree.chain(1), vec![3, 1]);
+        assert_eq!(tree.chain(0), vec![0]);
+        assert_eq!(tree.infectees_of(1), vec![2]);
+    }
+}
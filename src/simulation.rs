@@ -1,7 +1,14 @@
+use crate::Recording;
 use crate::recording::CountingTable;
 use crate::prelude::{Board, BoardBuilder};
+use crate::vaccination::Vaccination;
+use crate::policy::Policy;
+use crate::errors::{ActionError, BoardError};
 use getset::{Getters, Setters, MutGetters};
 use serde::{Serialize, Deserialize};
+use rand::SeedableRng;
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
 
 
 pub mod report;
@@ -17,12 +24,33 @@ pub struct SimulationBuilder {
     /// Report setup
     #[getset(get = "pub", set = "pub", get_mut = "pub")]
     pub report_plan: ReportPlan,
+    /// Vaccination campaign applied in every realization
+    ///
+    /// If `None`, nobody is vaccinated during the game.
+    #[getset(get = "pub", set = "pub", get_mut = "pub")]
+    #[serde(default)]
+    pub vaccination: Option<Vaccination>,
 }
 
 impl SimulationBuilder {
+	/// Returns the corresponding simulation.
+	///
+	/// # Panics
+	///
+	/// If the board configuration is not valid, see `try_build`.
 	pub fn build(self) -> Simulation {
-		let board = self.board_builder.build();
-		Simulation { board, report_plan: self.report_plan }
+		self.try_build().expect("building the simulation failed!")
+	}
+
+	/// Returns the corresponding simulation, after checking that the board configuration is valid.
+	///
+	/// # Errors
+	///
+	/// If the board configuration is not valid, see `BoardBuilder::try_build`.
+	pub fn try_build(self) -> Result<Simulation, BoardError> {
+		let mut board = self.board_builder.try_build()?;
+		board.set_vaccination(self.vaccination);
+		Ok(Simulation { board, report_plan: self.report_plan })
 	}
 }
 
@@ -47,7 +75,7 @@ impl Simulation {
     /// ```
     /// # use virus_alarm::prelude::*;
     /// let board = Board::default();
-    /// let report_plan = ReportPlan { num_simulations: 10, days: 10 };
+    /// let report_plan = ReportPlan { num_simulations: 10, days: 10, ..Default::default() };
     /// Simulation::new(board, report_plan);
     /// ```
     pub fn new(board: Board, report_plan: ReportPlan) -> Self {
@@ -55,40 +83,282 @@ impl Simulation {
     }
 
     /// Returns the result of the simulation.
+    ///
+    /// If the report plan has a seed, the result is reproducible.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use virus_alarm::prelude::*;
+    /// let report_plan = ReportPlan { num_simulations: 10, days: 10, seed: Some(1), ..Default::default() };
+    /// let simulation = Simulation::new(Board::default(), report_plan);
+    /// assert_eq!(simulation.run(), simulation.run());
+    /// ```
     pub fn run(&self) -> Report {
-        let mut counting_tables = Vec::new();
-        for _ in 0..*self.report_plan.num_simulations() {
+        match self.report_plan.seed() {
+            Some(seed) => self.run_with_rng(&mut rand_pcg::Pcg64::seed_from_u64(*seed)),
+            None => self.run_with_rng(&mut rand::thread_rng()),
+        }
+    }
+
+    /// Returns the result of the simulation, drawing randomness from `rng`.
+    ///
+    /// The seed of the report plan is ignored.
+    ///
+    /// # Remarks
+    ///
+    /// Each realization uses its own random number generator, seeded from `rng`.
+    pub fn run_with_rng<R: ?Sized + rand::Rng>(&self, rng: &mut R) -> Report {
+        let stop_rule = *self.report_plan.stop();
+        self.run_until_with_rng(rng, |recording| stop_rule.is_met(recording))
+    }
+
+    /// Returns the result of the simulation, where each realization stops 
+    /// as soon as `stop` holds for its recording.
+    ///
+    /// The stop rule of the report plan is ignored, but realizations never advance 
+    /// more than the number of days of the report plan.
+    ///
+    /// # Examples
+    ///
+    /// Stop as soon as there are ten sick individuals.
+    /// ```
+    /// # use virus_alarm::prelude::*;
+    /// let report_plan = ReportPlan { num_simulations: 10, days: 30, ..Default::default() };
+    /// let simulation = Simulation::new(Board::default(), report_plan);
+    /// let report = simulation.run_until(|recording| {
+    ///     recording.counting_table().last_day()[&Individual::Sick] >= 10
+    /// });
+    /// assert!(report.stopping_times().iter().all(|&days| days <= 30));
+    /// ```
+    pub fn run_until<F>(&self, stop: F) -> Report
+    where
+        F: Fn(&Recording) -> bool,
+    {
+        match self.report_plan.seed() {
+            Some(seed) => self.run_until_with_rng(&mut rand_pcg::Pcg64::seed_from_u64(*seed), stop),
+            None => self.run_until_with_rng(&mut rand::thread_rng(), stop),
+        }
+    }
+
+    /// Returns the result of the simulation, where each realization stops 
+    /// as soon as `stop` holds for its recording, drawing randomness from `rng`.
+    ///
+    /// The seed and stop rule of the report plan are ignored.
+    pub fn run_until_with_rng<R, F>(&self, rng: &mut R, stop: F) -> Report
+    where
+        R: ?Sized + rand::Rng,
+        F: Fn(&Recording) -> bool,
+    {
+        let mut report = Report::default();
+        for seed in self.realization_seeds(rng) {
+            let (recording, days) = self.run_realization(seed, &stop, &no_policy)
+                .expect("running the realization failed!");
+            report.push(&recording, days);
+        }
+        report
+    }
+
+    /// Returns the result of the simulation, where `policy` intervenes at the start of each day.
+    ///
+    /// If the report plan has a seed, the result is reproducible.
+    /// Immunizing more individuals than there are healthy ones immunizes all of them, see `Board::apply`.
+    ///
+    /// # Errors
+    ///
+    /// If any other action can not be taken, like closing a building that does not exist.
+    ///
+    /// # Remarks
+    ///
+    /// Only this method and `run_policy_with_rng` take a policy: 
+    /// `run_until`, `run_parallel` and `run_last_day` run the board without interventions.
+    ///
+    /// # Examples
+    ///
+    /// Vaccinate five individuals per day as soon as somebody is sick.
+    /// ```
+    /// # use virus_alarm::prelude::*;
+    /// # use virus_alarm::{Recording, policy::Action};
+    /// let report_plan = ReportPlan { num_simulations: 10, days: 10, ..Default::default() };
+    /// let simulation = Simulation::new(Board::default(), report_plan);
+    /// let report = simulation.run_policy(&|recording: &Recording| {
+    ///     if recording.counting_table().last_day()[&Individual::Sick] > 0 {
+    ///         vec![Action::Immunize(5)]
+    ///     } else {
+    ///         Vec::new()
+    ///     }
+    /// }).unwrap();
+    /// assert_eq!(report.counting_tables().len(), 10);
+    /// ```
+    ///
+    /// Closing a building that does not exist.
+    /// ```
+    /// # use virus_alarm::prelude::*;
+    /// # use virus_alarm::{Recording, errors::ActionError, policy::Action};
+    /// let report_plan = ReportPlan { num_simulations: 1, days: 1, ..Default::default() };
+    /// let simulation = Simulation::new(Board::default(), report_plan);
+    /// let report = simulation.run_policy(&|_: &Recording| vec![Action::Close("Zoo".to_string())]);
+    /// assert_eq!(report.err(), Some(ActionError::UnknownBuilding("Zoo".to_string())));
+    /// ```
+    pub fn run_policy<P: Policy>(&self, policy: &P) -> Result<Report, ActionError> {
+        match self.report_plan.seed() {
+            Some(seed) => self.run_policy_with_rng(&mut rand_pcg::Pcg64::seed_from_u64(*seed), policy),
+            None => self.run_policy_with_rng(&mut rand::thread_rng(), policy),
+        }
+    }
+
+    /// Returns the result of the simulation, where `policy` intervenes at the start of each day, 
+    /// drawing randomness from `rng`.
+    ///
+    /// The seed of the report plan is ignored.
+    ///
+    /// # Errors
+    ///
+    /// See `run_policy`.
+    pub fn run_policy_with_rng<R, P>(&self, rng: &mut R, policy: &P) -> Result<Report, ActionError>
+    where
+        R: ?Sized + rand::Rng,
+        P: Policy,
+    {
+        let stop_rule = *self.report_plan.stop();
+        let stop = |recording: &Recording| stop_rule.is_met(recording);
+        let mut report = Report::default();
+        for seed in self.realization_seeds(rng) {
+            let (recording, days) = self.run_realization(seed, &stop, policy)?;
+            report.push(&recording, days);
+        }
+        Ok(report)
+    }
+
+    /// Returns the result of the simulation, distributing realizations across threads.
+    ///
+    /// The result is the same as `run`, regardless of the number of threads.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use virus_alarm::prelude::*;
+    /// let report_plan = ReportPlan { num_simulations: 10, days: 10, seed: Some(1), ..Default::default() };
+    /// let simulation = Simulation::new(Board::default(), report_plan);
+    /// assert_eq!(simulation.run_parallel(), simulation.run());
+    /// ```
+    #[cfg(feature = "parallel")]
+    pub fn run_parallel(&self) -> Report {
+        match self.report_plan.seed() {
+            Some(seed) => self.run_parallel_with_rng(&mut rand_pcg::Pcg64::seed_from_u64(*seed)),
+            None => self.run_parallel_with_rng(&mut rand::thread_rng()),
+        }
+    }
+
+    /// Returns the result of the simulation, distributing realizations across threads 
+    /// and drawing randomness from `rng`.
+    ///
+    /// The result is the same as `run_with_rng`, regardless of the number of threads.
+    #[cfg(feature = "parallel")]
+    pub fn run_parallel_with_rng<R: ?Sized + rand::Rng>(&self, rng: &mut R) -> Report {
+        let stop_rule = *self.report_plan.stop();
+        let stop = |recording: &Recording| stop_rule.is_met(recording);
+        let realizations: Vec<(Recording, usize)> = self.realization_seeds(rng)
+            .into_par_iter()
+            .map(|seed| self.run_realization(seed, &stop, &no_policy).expect("running the realization failed!"))
+            .collect();
+        let mut report = Report::default();
+        for (recording, days) in realizations {
+            report.push(&recording, days);
+        }
+        report
+    }
+
+    /// Draws one seed per realization.
+    fn realization_seeds<R: ?Sized + rand::Rng>(&self, rng: &mut R) -> Vec<u64> {
+        (0..*self.report_plan.num_simulations()).map(|_| rng.gen()).collect()
+    }
+
+    /// Runs one realization of the game, with its own random number generator, until `stop` holds, 
+    /// where `policy` intervenes at the start of each day.
+    ///
+    /// Returns the recording and the number of days advanced.
+    ///
+    /// # Errors
+    ///
+    /// If an action of `policy` can not be taken, except for immunizing more individuals than there are healthy ones.
+    fn run_realization<F, P>(&self, seed: u64, stop: &F, policy: &P) -> Result<(Recording, usize), ActionError>
+    where
+        F: Fn(&Recording) -> bool,
+        P: Policy + ?Sized,
+    {
+        let mut board = self.board.clone();
+        let mut rng = rand_pcg::Pcg64::seed_from_u64(seed);
+        let mut days = 0;
+        while days < *self.report_plan.days() && !stop(board.recording()) {
+            for action in policy.actions(board.recording()) {
+                match board.apply(action) {
+                    // All healthy individuals were immunized
+                    Ok(_) | Err(ActionError::NoHealthyLeft) => {},
+                    Err(error) => return Err(error),
+                }
+            }
+            board.advance_with_rng(&mut rng);
+            days += 1;
+        }
+        Ok((board.recording().clone(), days))
+    }
+
+    /// Returns the result of the last day of the simulation, 
+    /// grouped by individual variant.
+    ///
+    /// # Remarks
+    ///
+    /// Intermediate days are not recorded, which makes it lighter than `run` 
+    /// for a large number of simulations. The stop rule of the report plan is ignored.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use virus_alarm::prelude::*;
+    /// let report_plan = ReportPlan { num_simulations: 10, days: 10, seed: Some(1), ..Default::default() };
+    /// let simulation = Simulation::new(Board::default(), report_plan);
+    /// let last_day_report = simulation.run_last_day();
+    /// let report = simulation.run();
+    /// assert_eq!(
+    ///     last_day_report.individual_last(&Individual::Healthy).iter().collect::<Vec<_>>(), 
+    ///     report.individual_last(&Individual::Healthy)
+    /// );
+    /// ```
+    pub fn run_last_day(&self) -> LastDayReport {
+        match self.report_plan.seed() {
+            Some(seed) => self.run_last_day_with_rng(&mut rand_pcg::Pcg64::seed_from_u64(*seed)),
+            None => self.run_last_day_with_rng(&mut rand::thread_rng()),
+        }
+    }
+
+    /// Returns the result of the last day of the simulation, drawing randomness from `rng`.
+    ///
+    /// The seed of the report plan is ignored.
+    pub fn run_last_day_with_rng<R: ?Sized + rand::Rng>(&self, rng: &mut R) -> LastDayReport {
+        let mut last_day_report = LastDayReport::new();
+        for seed in self.realization_seeds(rng) {
             let mut board = self.board.clone();
-            board.advance_many(*self.report_plan.days());
-            counting_tables.push(board.counting_table().clone());
+            let mut rng = rand_pcg::Pcg64::seed_from_u64(seed);
+            for _ in 0..*self.report_plan.days() {
+                board.advance_population_with_rng(&mut rng);
+            } 
+            last_day_report.push(board.counting_all());
         }
-        Report { counting_tables }
-    }
-
-    // /// Returns the result of the last day of the simulation, 
-    // /// grouped by individual variant.
-    // pub fn run_last_day(&self) -> HashMap<Individual, Vec<usize>> {
-    //     let mut hm: HashMap<Individual, Vec<usize>> = 
-    //         Individual::iter().map(|i| (i, Vec::new())).collect();
-
-    //     for _ in 0..*self.report_plan.num_simulations() {
-    //         let mut board = self.board.clone();
-    //         for _ in 0..*self.report_plan.days() {
-    //             board.advance_population();
-    //         } 
-    //         let hm_sim = board.population().counting_all();
-    //         for individual in Individual::iter() {
-    //             hm.entry(individual).or_insert(Vec::new()).push(hm_sim[&individual]);
-    //         }
-    //     }
-    //     hm
-    // }
+        last_day_report
+    }
+}
+
+/// Policy that never intervenes.
+fn no_policy(_: &Recording) -> Vec<crate::policy::Action> {
+    Vec::new()
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::{Individual, building::Spreading};
+    use crate::{Individual, building::Spreading, recording::Flow};
+    use strum::IntoEnumIterator;
 
     #[test]
     fn run() {
@@ -102,21 +372,30 @@ mod tests {
                     immune: 20,
                     buildings: vec![(0, 0)],
                     spreading: Spreading::OneNear,
+                    ..Default::default()
             },
             report_plan: ReportPlan{
                     num_simulations: 1,
                     days: 0,
-            }
+                    ..Default::default()
+            },
+            vaccination: None,
         };
         let simulation = simulation_builder.build();
         let report = simulation.run();
-        let expected = CountingTable::from(vec![
+        let mut expected = CountingTable::from(vec![
             (Individual::Healthy, vec![100]), 
+            (Individual::Exposed, vec![0]), 
             (Individual::Infected1, vec![0]), 
             (Individual::Infected2, vec![0]), 
             (Individual::Infected3, vec![0]), 
+            (Individual::Asymptomatic, vec![0]), 
             (Individual::Sick, vec![3]), 
-            (Individual::Immune, vec![20])]);
+            (Individual::Immune, vec![20]), 
+            (Individual::Dead, vec![0])]);
+        *expected.flows_mut() = Flow::iter().map(|flow| (flow, vec![0])).collect();
+        *expected.quarantined_mut() = vec![0];
+        *expected.overload_mut() = vec![0];
         assert_eq!(report.counting_tables(), &vec![expected]);
     }
 
@@ -132,21 +411,30 @@ mod tests {
                     immune: 20,
                     buildings: vec![(2, 2)],
                     spreading: Spreading::OneNear,
+                    ..Default::default()
             },
             report_plan: ReportPlan{
                     num_simulations: 1,
                     days: 1,
-            }
+                    ..Default::default()
+            },
+            vaccination: None,
         };
         let simulation = simulation_builder.build();
         let report = simulation.run();
-        let expected = CountingTable::from(vec![
+        let mut expected = CountingTable::from(vec![
             (Individual::Healthy, vec![100, 100]), 
+            (Individual::Exposed, vec![0, 0]), 
             (Individual::Infected1, vec![0, 0]), 
             (Individual::Infected2, vec![0, 0]), 
             (Individual::Infected3, vec![0, 0]), 
+            (Individual::Asymptomatic, vec![0, 0]), 
             (Individual::Sick, vec![3, 3]), 
-            (Individual::Immune, vec![20, 20])]);
+            (Individual::Immune, vec![20, 20]), 
+            (Individual::Dead, vec![0, 0])]);
+        *expected.flows_mut() = Flow::iter().map(|flow| (flow, vec![0, 0])).collect();
+        *expected.quarantined_mut() = vec![0, 0];
+        *expected.overload_mut() = vec![0, 0];
         assert_eq!(report.counting_tables(), &vec![expected]);
     }
 
@@ -162,24 +450,49 @@ mod tests {
                     immune: 0,
                     buildings: vec![(200, 200)],
                     spreading: Spreading::OneNear,
+                    ..Default::default()
             },
             report_plan: ReportPlan{
                     num_simulations: 1,
                     days: 1,
-            }
+                    ..Default::default()
+            },
+            vaccination: None,
         };
         let simulation = simulation_builder.build();
         let report = simulation.run();
-        let expected = CountingTable::from(vec![
+        let mut expected = CountingTable::from(vec![
             (Individual::Healthy, vec![100, 99]), 
+            (Individual::Exposed, vec![0, 0]), 
             (Individual::Infected1, vec![1, 1]), 
             (Individual::Infected2, vec![0, 1]), 
             (Individual::Infected3, vec![0, 0]), 
+            (Individual::Asymptomatic, vec![0, 0]), 
             (Individual::Sick, vec![3, 3]), 
-            (Individual::Immune, vec![0, 0])]);
+            (Individual::Immune, vec![0, 0]), 
+            (Individual::Dead, vec![0, 0])]);
+        *expected.flows_mut() = Flow::iter().map(|flow| (flow, vec![0, 0])).collect();
+        *expected.quarantined_mut() = vec![0, 0];
+        *expected.overload_mut() = vec![0, 0];
+        expected.flows_mut().insert(Flow::Infection, vec![0, 1]);
         assert_eq!(report.counting_tables(), &vec![expected]);
     }
 
+    #[test]
+    fn run_last_day_with_rng() {
+        let report_plan = ReportPlan { num_simulations: 20, days: 10, ..Default::default() };
+        let simulation = Simulation::new(Board::default(), report_plan);
+        let report = simulation.run_with_rng(&mut crate::tests::rng(1));
+        let last_day_report = simulation.run_last_day_with_rng(&mut crate::tests::rng(1));
+        assert_eq!(last_day_report.num_simulations(), 20);
+        for individual in Individual::iter() {
+            assert_eq!(
+                last_day_report.individual_last(&individual).iter().collect::<Vec<_>>(), 
+                report.individual_last(&individual)
+            );
+        }
+    }
+
     #[test]
     fn run_last_day() {
         let simulation_builder = SimulationBuilder {
@@ -192,11 +505,14 @@ mod tests {
                     immune: 0,
                     buildings: vec![(200, 200)],
                     spreading: Spreading::OneNear,
+                    ..Default::default()
             },
             report_plan: ReportPlan{
                     num_simulations: 1,
                     days: 1,
-            }
+                    ..Default::default()
+            },
+            vaccination: None,
         };
         let report = simulation_builder.build().run();
         let result = vec![
@@ -217,4 +533,100 @@ mod tests {
             ];
         assert_eq!(result, expected);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn run_with_seed() {
+        let simulation_builder = SimulationBuilder {
+            board_builder: BoardBuilder{
+                    healthy: 98,
+                    infected1: 2,
+                    infected2: 0,
+                    infected3: 0,
+                    sick: 0,
+                    immune: 0,
+                    buildings: vec![(5, 4), (2, 2), (4, 4)],
+                    spreading: Spreading::OneNear,
+                    ..Default::default()
+            },
+            report_plan: ReportPlan{
+                    num_simulations: 10,
+                    days: 5,
+                    seed: Some(1),
+                    ..Default::default()
+            },
+            vaccination: None,
+        };
+        let simulation = simulation_builder.build();
+        assert_eq!(simulation.run(), simulation.run());
+        assert_eq!(simulation.run_with_rng(&mut crate::tests::rng(1)), simulation.run_with_rng(&mut crate::tests::rng(1)));
+    }
+
+    #[test]
+    fn run_policy() {
+        let simulation_builder = SimulationBuilder {
+            board_builder: BoardBuilder { healthy: 10, infected1: 1, buildings: vec![(4, 4)], ..Default::default() },
+            report_plan: ReportPlan { num_simulations: 5, days: 4, seed: Some(1), ..Default::default() },
+            vaccination: None,
+        };
+        let simulation = simulation_builder.build();
+        let total_infections = |report: &Report| report.flow_total(&Flow::Infection).iter().sum::<usize>();
+        assert!(total_infections(&simulation.run()) > 0);
+        // Lockdown from the first day
+        let lockdown = |_: &Recording| vec![crate::policy::Action::Close("Defult".to_string())];
+        assert_eq!(total_infections(&simulation.run_policy(&lockdown).unwrap()), 0);
+        // A policy that never intervenes changes nothing
+        assert_eq!(simulation.run_policy(&no_policy).unwrap(), simulation.run());
+        // Policy doses are recorded in the day they are given, without changing previous days
+        let immunize = |recording: &Recording| if recording.timeline() == &2 { vec![crate::policy::Action::Immunize(20)] } else { Vec::new() };
+        for counting_table in simulation.run_policy(&immunize).unwrap().counting_tables() {
+            assert_eq!(counting_table.inner()[&Individual::Healthy][3], 0);
+            assert!(counting_table.inner()[&Individual::Immune][..3].iter().all(|&immune| immune == 0));
+        }
+    }
+
+    #[test]
+    fn run_with_vaccination() {
+        let simulation_builder = SimulationBuilder {
+            board_builder: BoardBuilder { healthy: 10, immune: 2, ..Default::default() },
+            report_plan: ReportPlan { num_simulations: 2, days: 4, ..Default::default() },
+            vaccination: Some(Vaccination { doses_per_day: 4, start_day: 2, ..Default::default() }),
+        };
+        let simulation = simulation_builder.build();
+        for counting_table in simulation.run().counting_tables() {
+            assert_eq!(counting_table.inner()[&Individual::Immune], vec![2, 2, 6, 10, 12]);
+        }
+        assert_eq!(simulation.run_last_day().individual_last(&Individual::Immune), &vec![12, 12]);
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn run_parallel() {
+        let report_plan = ReportPlan { num_simulations: 20, days: 10, ..Default::default() };
+        let simulation = Simulation::new(Board::default(), report_plan);
+        let serial = simulation.run_with_rng(&mut crate::tests::rng(1));
+        for num_threads in 1..4 {
+            let pool = rayon::ThreadPoolBuilder::new().num_threads(num_threads).build().unwrap();
+            let parallel = pool.install(|| simulation.run_parallel_with_rng(&mut crate::tests::rng(1)));
+            assert_eq!(parallel, serial);
+        }
+    }
+
+    #[test]
+    fn run_contained() {
+        let report_plan = ReportPlan { num_simulations: 20, days: 50, stop: StopRule::Contained, ..Default::default() };
+        let simulation = Simulation::new(Board::default(), report_plan);
+        let report = simulation.run_with_rng(&mut crate::tests::rng(1));
+        for (counting_table, &days) in report.counting_tables().iter().zip(report.stopping_times()) {
+            assert_eq!(counting_table.days(), days + 1);
+            assert!(counting_table.is_contained() || days == 50);
+        }
+    }
+
+    #[test]
+    fn run_until() {
+        let report_plan = ReportPlan { num_simulations: 5, days: 10, ..Default::default() };
+        let simulation = Simulation::new(Board::default(), report_plan);
+        let report = simulation.run_until_with_rng(&mut crate::tests::rng(1), |recording| *recording.timeline() >= 3);
+        assert_eq!(report.stopping_times(), &vec![3; 5]);
+    }
+}
@@ -1,6 +1,10 @@
+use std::collections::HashMap;
 use crate::simulation::CountingTable;
+use crate::recording::{Attendance, Flow};
 use ndarray::Array2;
+use average::Estimate;
 use crate::prelude::{Individual};
+use crate::Recording;
 use getset::{Getters, Setters, MutGetters};
 use serde::{Serialize, Deserialize};
 use strum::IntoEnumIterator;
@@ -12,8 +16,52 @@ pub struct ReportPlan {
     #[getset(get = "pub", set = "pub", get_mut = "pub")]
     pub num_simulations: usize,
     /// Number of days the game advances
+    ///
+    /// If the stop rule is met before, the game advances fewer days.
     #[getset(get = "pub", set = "pub", get_mut = "pub")]
     pub days: usize,
+    /// Seed for the random number generator, so that the simulation can be reproduced.
+    ///
+    /// If `None`, a fresh source of randomness is used every time.
+    #[getset(get = "pub", set = "pub", get_mut = "pub")]
+    #[serde(default)]
+    pub seed: Option<u64>,
+    /// Rule that determines when each realization of the game stops.
+    #[getset(get = "pub", set = "pub", get_mut = "pub")]
+    #[serde(default)]
+    pub stop: StopRule,
+}
+
+/// Rule that determines when a realization of the game stops.
+///
+/// In any case, a realization stops after the number of days of the report plan.
+/// For arbitrary rules, see `Simulation::run_until`.
+#[derive(Debug, Hash, Copy, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub enum StopRule {
+    /// Stops after the number of days of the report plan
+    #[default]
+    Days,
+    /// Stops as soon as the outbreak is contained, see `Recording::is_contained`
+    Contained,
+}
+
+impl StopRule {
+    /// Returns `true` if the game should stop, given its recording.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use virus_alarm::prelude::*;
+    /// let board = Board::default();
+    /// assert!(!StopRule::Days.is_met(board.recording()));
+    /// assert!(!StopRule::Contained.is_met(board.recording()));
+    /// ```
+    pub fn is_met(&self, recording: &Recording) -> bool {
+        match self {
+            StopRule::Days => false,
+            StopRule::Contained => recording.is_contained(),
+        }
+    }
 }
 
 /// Report of a simulation of a game.
@@ -22,12 +70,41 @@ pub struct Report {
     /// Counting tables.
     #[getset(get = "pub")]
     pub(crate) counting_tables: Vec<CountingTable>,
+    /// Number of days each realization advanced before stopping.
+    #[getset(get = "pub")]
+    pub(crate) stopping_times: Vec<usize>,
+    /// Score obtained per day, see `Recording::daily_score`.
+    ///
+    /// Empty if the report was built from counting tables, which do not keep the score.
+    #[getset(get = "pub")]
+    pub(crate) daily_scores: Vec<Vec<isize>>,
+    /// Visitors and infections per building and day, see `Recording::attendance`.
+    #[getset(get = "pub")]
+    pub(crate) attendances: Vec<Vec<Attendance>>,
 }
 
 impl Report {
+    /// Adds the recording of a realization, which advanced `stopping_time` days.
+    pub(crate) fn push(&mut self, recording: &Recording, stopping_time: usize) -> &mut Self {
+        self.counting_tables.push(recording.counting_table().clone());
+        self.stopping_times.push(stopping_time);
+        self.daily_scores.push(recording.daily_score().clone());
+        self.attendances.push(recording.attendance().clone());
+        self
+    }
+
     /// Returns the number of days in all simulations
+    ///
+    /// # Remarks
+    ///
+    /// If realizations stopped at different times, this is the maximum over all of them.
     pub fn days(&self) -> usize {
-        self.counting_tables()[0].days()
+        self.counting_tables().iter().map(|counting_table| counting_table.days()).max().unwrap_or(0)
+    }
+
+    /// Returns the average number of days realizations advanced before stopping.
+    pub fn stopping_time_average(&self) -> average::Variance {
+        self.stopping_times().iter().map(|&t| t as f64).collect()
     }
 
     /// Returns the average "counting table" over all simulations. 
@@ -35,6 +112,7 @@ impl Report {
     /// # Remarks
     ///
     /// It can not return a `CountingTable` since the averages are `f64`, so it returns the numerical table only.
+    /// Realizations that stopped earlier only contribute to the days they reached.
     pub fn average_counting_table(&self) -> Array2<average::Variance> {
         let individual_variants_num = Individual::iter().len();
         if self.counting_tables.is_empty() {
@@ -45,7 +123,10 @@ impl Report {
             let counting_tables: Vec<_> = self.counting_tables().iter().map(Array2::from).collect();
             for row in 0..individual_variants_num {
                 for col in 0..days {
-                    average_array[[row, col]] = counting_tables.iter().map(|counting_table| counting_table[[row, col]] as f64).collect();
+                    average_array[[row, col]] = counting_tables.iter()
+                        .filter_map(|counting_table| counting_table.get([row, col]))
+                        .map(|&x| x as f64)
+                        .collect();
                 }
             }
             average_array
@@ -77,11 +158,12 @@ impl Report {
     /// # Remarks
     ///
     /// Realizations that do not have healthy individuals are omitted.
+    /// Realizations that stopped earlier are omitted in the days they did not reach.
     pub fn individual_transpose(&self, individual: &Individual) -> Vec<Vec<usize>> {
         let mut vec = Vec::new();
         let all = self.individual(individual);
         for day in 0..self.days() {
-            vec.push( all.iter().map(|realization| realization[day]).collect() );
+            vec.push( all.iter().filter_map(|realization| realization.get(day).copied()).collect() );
         }
         vec
     }
@@ -118,11 +200,239 @@ impl Report {
             vec[0][0]
         }
     }
+
+    /// Returns the trajectory over time of a flow for all realizations, see `Flow`.
+    ///
+    /// # Remarks
+    ///
+    /// Realizations that do not count the flow are omitted.
+    pub fn flow(&self, flow: &Flow) -> Vec<&Vec<usize>> {
+        self.counting_tables().iter()
+            .filter_map(|counting_table| counting_table.flows().get(flow))
+            .collect()
+    }
+
+    /// Returns the average (per day) of a flow over all simulations.
+    ///
+    /// # Remarks
+    ///
+    /// Realizations that stopped earlier only contribute to the days they reached.
+    pub fn flow_average(&self, flow: &Flow) -> Vec<average::Variance> {
+        let all = self.flow(flow);
+        (0..self.days())
+            .map(|day| all.iter().filter_map(|realization| realization.get(day)).map(|&x| x as f64).collect())
+            .collect()
+    }
+
+    /// Returns the total of a flow over all days, for each realization.
+    ///
+    /// # Examples
+    ///
+    /// Total number of infections, which counts reinfections.
+    /// ```
+    /// # use virus_alarm::prelude::*;
+    /// # use virus_alarm::recording::Flow;
+    /// let simulation = SimulationBuilder {
+    ///     board_builder: BoardBuilder { healthy: 5, infected1: 1, buildings: vec![(3, 2)], ..Default::default() },
+    ///     report_plan: ReportPlan { num_simulations: 2, days: 5, ..Default::default() },
+    ///     vaccination: None,
+    /// }.build();
+    /// let report = simulation.run();
+    /// assert!(report.flow_total(&Flow::Infection).iter().all(|&total| total <= 5));
+    /// ```
+    pub fn flow_total(&self, flow: &Flow) -> Vec<usize> {
+        self.flow(flow).into_iter()
+            .map(|v| v.iter().sum())
+            .collect()
+    }
+
+    /// Returns the total score of each realization, see `Recording::score`.
+    ///
+    /// Returns `None` if the report was built from counting tables, which do not keep the score.
+    ///
+    /// # Examples
+    ///
+    /// Closing buildings is penalized.
+    /// ```
+    /// # use virus_alarm::prelude::*;
+    /// # use virus_alarm::{Recording, policy::Action};
+    /// let simulation = SimulationBuilder {
+    ///     board_builder: BoardBuilder { healthy: 5, buildings: vec![(3, 2)], penalty: 10, ..Default::default() },
+    ///     report_plan: ReportPlan { num_simulations: 2, days: 3, ..Default::default() },
+    ///     vaccination: None,
+    /// }.build();
+    /// assert_eq!(simulation.run().score(), Some(vec![15, 15]));
+    /// let lockdown = |_: &Recording| vec![Action::Close("Defult".to_string())];
+    /// assert_eq!(simulation.run_policy(&lockdown).unwrap().score(), Some(vec![-15, -15]));
+    /// ```
+    pub fn score(&self) -> Option<Vec<isize>> {
+        if self.daily_scores.len() != self.counting_tables.len() {
+            return None;
+        }
+        let score = self.daily_scores().iter()
+            .map(|daily_score| daily_score.iter().sum())
+            .collect();
+        Some(score)
+    }
+
+    /// Returns the average total score over all realizations.
+    ///
+    /// Returns `None` if the report was built from counting tables, see `score`.
+    pub fn score_average(&self) -> Option<average::Variance> {
+        let score = self.score()?;
+        Some(score.iter().map(|&score| score as f64).collect())
+    }
+
+    /// Returns the probability that the hospital is overloaded in some day, 
+    /// estimated as the fraction of realizations where it happened, see `Hospital`.
+    ///
+    /// # Examples
+    ///
+    /// Flattening the curve: the fewer seats in buildings, the lower the risk of overload.
+    /// ```
+    /// # use virus_alarm::prelude::*;
+    /// # use virus_alarm::hospital::Hospital;
+    /// let overload_probability = |buildings| SimulationBuilder {
+    ///     board_builder: BoardBuilder { 
+    ///         healthy: 20, 
+    ///         infected1: 1, 
+    ///         buildings, 
+    ///         hospital: Some(Hospital { beds: 2, ..Default::default() }),
+    ///         ..Default::default() 
+    ///     },
+    ///     report_plan: ReportPlan { num_simulations: 10, days: 10, seed: Some(1), ..Default::default() },
+    ///     vaccination: None,
+    /// }.build().run().overload_probability();
+    /// assert_eq!(overload_probability(vec![(4, 5)]), 1.);
+    /// assert_eq!(overload_probability(vec![(2, 1)]), 0.1);
+    /// ```
+    pub fn overload_probability(&self) -> f64 {
+        if self.counting_tables().is_empty() {
+            0.
+        } else {
+            let overloaded = self.counting_tables().iter().filter(|counting_table| counting_table.is_overloaded()).count();
+            overloaded as f64 / self.counting_tables().len() as f64
+        }
+    }
+
+    /// Returns the average total number of infections in each building over all realizations,
+    /// together with the name of the building, in the order of the buildings of the board.
+    ///
+    /// # Examples
+    ///
+    /// The school, where everyone meets, drives the epidemic.
+    /// ```
+    /// # use virus_alarm::prelude::*;
+    /// # use virus_alarm::Building;
+    /// let board = Board::new(
+    ///     Population::from(vec![Individual::Infected1, Individual::Healthy, Individual::Healthy]),
+    ///     vec![Building::new(2, 2, "School")],
+    /// );
+    /// let simulation = Simulation::new(board, ReportPlan { num_simulations: 3, days: 2, ..Default::default() });
+    /// let report = simulation.run();
+    /// let infections = report.infections_by_building();
+    /// assert_eq!(infections[0].0, "School");
+    /// assert!(infections[0].1.mean() > 0.);
+    /// ```
+    pub fn infections_by_building(&self) -> Vec<(String, average::Variance)> {
+        self.by_building(Attendance::total_infections)
+    }
+
+    /// Returns the average total number of visitors of each building over all realizations,
+    /// together with the name of the building, in the order of the buildings of the board.
+    pub fn visitors_by_building(&self) -> Vec<(String, average::Variance)> {
+        self.by_building(Attendance::total_visitors)
+    }
+
+    /// Returns the average total number of visitors carrying the virus of each building over all realizations,
+    /// together with the name of the building, in the order of the buildings of the board.
+    pub fn infected_visitors_by_building(&self) -> Vec<(String, average::Variance)> {
+        self.by_building(Attendance::total_infected_visitors)
+    }
+
+    /// Returns the average of `total` for each building over all realizations.
+    fn by_building<F: Fn(&Attendance) -> usize>(&self, total: F) -> Vec<(String, average::Variance)> {
+        let buildings = self.attendances().iter().map(|attendance| attendance.len()).max().unwrap_or(0);
+        (0..buildings)
+            .map(|index| {
+                let realizations = self.attendances().iter().filter_map(|attendance| attendance.get(index));
+                let name = realizations.clone().next().map(|attendance| attendance.name().clone()).unwrap_or_default();
+                (name, realizations.map(|attendance| total(attendance) as f64).collect())
+            })
+            .collect()
+    }
+}
+
+/// Report of the last day of a simulation of a game.
+///
+/// # Remarks
+///
+/// Only the counting of the last day is kept for each realization, 
+/// one number per individual variant instead of a whole counting table, 
+/// together with their mean and variance computed online, see `Simulation::run_last_day`.
+#[derive(Debug, Clone, Getters)]
+pub struct LastDayReport {
+    /// Counting of each individual variant in the last day, one value per realization.
+    #[getset(get = "pub")]
+    pub(crate) last_day: HashMap<Individual, Vec<usize>>,
+    /// Mean and variance of the counting of each individual variant in the last day, 
+    /// computed online over all realizations.
+    #[getset(get = "pub")]
+    pub(crate) average: HashMap<Individual, average::Variance>,
+}
+
+impl LastDayReport {
+    /// Creates an empty report.
+    pub(crate) fn new() -> Self {
+        LastDayReport { 
+            last_day: Individual::iter().map(|i| (i, Vec::new())).collect(),
+            average: Individual::iter().map(|i| (i, average::Variance::new())).collect(),
+        }
+    }
+
+    /// Adds the counting of the last day of a realization.
+    pub(crate) fn push(&mut self, counting: HashMap<Individual, usize>) -> &mut Self {
+        for individual in Individual::iter() {
+            let value = counting.get(&individual).copied().unwrap_or(0);
+            self.last_day.entry(individual).or_default().push(value);
+            self.average.entry(individual).or_default().add(value as f64);
+        }
+        self
+    }
+
+    /// Returns the number of realizations in the report.
+    pub fn num_simulations(&self) -> usize {
+        self.last_day.get(&Individual::Healthy).map(|v| v.len()).unwrap_or(0)
+    }
+
+    /// Returns the number of individuals of a variant in the last day, for all realizations.
+    pub fn individual_last(&self, individual: &Individual) -> &Vec<usize> {
+        &self.last_day[individual]
+    }
+
+    /// Returns the mean and variance of the number of individuals of a variant in the last day.
+    pub fn individual_average(&self, individual: &Individual) -> &average::Variance {
+        &self.average[individual]
+    }
+}
+
+impl Default for LastDayReport {
+    fn default() -> Self { 
+        LastDayReport::new()
+    }
 }
 
 impl From<Vec<CountingTable>> for Report {
+    /// Each counting table is assumed to be the full recording of a realization.
+    /// Scores are not known, see `Report::score`.
     fn from(counting_tables: Vec<CountingTable>) -> Self { 
-        Report { counting_tables } 
+        let stopping_times = counting_tables.iter()
+            .map(|counting_table| counting_table.days().saturating_sub(1))
+            .collect();
+        // Counting tables do not keep the score
+        let daily_scores = Vec::new();
+        let attendances = vec![Vec::new(); counting_tables.len()];
+        Report { counting_tables, stopping_times, daily_scores, attendances } 
     }
 }
 
@@ -137,11 +447,12 @@ mod tests {
             Individual::iter().map(|i| (i, vec![0])).collect(),
             Individual::iter().map(|i| (i, vec![1])).collect()
         ];
-        let report = Report { counting_tables };
+        let report = Report::from(counting_tables);
+        assert_eq!(report.score(), None);
         let average_counting_table = report.average_counting_table();
         let variance: average::Variance = vec![0., 1.].into_iter().collect();
-        assert_eq!(average_counting_table.map(|v| v.mean()), Array2::from_elem((6, 1), variance.mean()));
-        assert_eq!(average_counting_table.map(|v| v.error()), Array2::from_elem((6, 1), variance.error()));
+        assert_eq!(average_counting_table.map(|v| v.mean()), Array2::from_elem((9, 1), variance.mean()));
+        assert_eq!(average_counting_table.map(|v| v.error()), Array2::from_elem((9, 1), variance.error()));
     }
 
     #[test]
@@ -150,7 +461,7 @@ mod tests {
             Individual::iter().map(|i| (i, vec![0, 0])).collect(),
             Individual::iter().map(|i| (i, vec![1, 2])).collect()
         ];
-        let report = Report { counting_tables };
+        let report = Report::from(counting_tables);
         assert_eq!(report.individual(&Individual::Healthy), vec![&vec![0, 0], &vec![1, 2]]);
     }
 
@@ -160,7 +471,7 @@ mod tests {
             Individual::iter().map(|i| (i, vec![0, 0])).collect(),
             Individual::iter().map(|i| (i, vec![1, 2])).collect()
         ];
-        let report = Report { counting_tables };
+        let report = Report::from(counting_tables);
         assert_eq!(report.individual_transpose(&Individual::Healthy), vec![vec![0, 1], vec![0, 2]]);
     }
 
@@ -171,7 +482,7 @@ mod tests {
             Individual::iter().map(|i| (i, vec![8, 9])).collect(),
             Individual::iter().map(|i| (i, vec![16, 0])).collect(),
         ];
-        let report = Report { counting_tables };
+        let report = Report::from(counting_tables);
         let average_healthy = report.individual_average(&Individual::Healthy);
         assert_eq!(average_healthy.iter().map(|v| v.mean()).collect::<Vec<f64>>(), vec![8.0, 3.0]);
         assert_eq!(average_healthy.iter().map(|v| v.error()).collect::<Vec<f64>>(), vec![4.618802153517006, 3.0]);
@@ -183,17 +494,90 @@ mod tests {
             Individual::iter().map(|i| (i, vec![0, 0])).collect(),
             Individual::iter().map(|i| (i, vec![1, 2])).collect()
         ];
-        let report = Report { counting_tables };
+        let report = Report::from(counting_tables);
         assert_eq!(report.individual_last(&Individual::Healthy), vec![&0, &2]);
     }
 
+    #[test]
+    fn last_day_report() {
+        let mut report = LastDayReport::new();
+        report.push(Individual::iter().map(|i| (i, 0)).collect());
+        report.push(Individual::iter().map(|i| (i, 2)).collect());
+        assert_eq!(report.num_simulations(), 2);
+        assert_eq!(report.individual_last(&Individual::Healthy), &vec![0, 2]);
+        assert_eq!(report.individual_average(&Individual::Sick).mean(), 1.);
+        assert_eq!(report.individual_average(&Individual::Sick).sample_variance(), 2.);
+    }
+
+    #[test]
+    fn stopped_realizations() {
+        let counting_tables: Vec<CountingTable> = vec![
+            Individual::iter().map(|i| (i, vec![0])).collect(),
+            Individual::iter().map(|i| (i, vec![1, 2])).collect()
+        ];
+        let report = Report::from(counting_tables);
+        assert_eq!(report.days(), 2);
+        assert_eq!(report.stopping_times(), &vec![0, 1]);
+        assert_eq!(report.stopping_time_average().mean(), 0.5);
+        assert_eq!(report.individual_transpose(&Individual::Healthy), vec![vec![0, 1], vec![2]]);
+        assert_eq!(report.average_counting_table().map(|v| v.mean())[[0, 1]], 2.);
+    }
+
+    #[test]
+    fn flows() {
+        let mut counting_tables: Vec<CountingTable> = vec![
+            Individual::iter().map(|i| (i, vec![0, 0, 0])).collect(),
+            Individual::iter().map(|i| (i, vec![1, 2])).collect()
+        ];
+        counting_tables[0].flows_mut().insert(Flow::Recovery, vec![0, 2, 4]);
+        counting_tables[1].flows_mut().insert(Flow::Recovery, vec![0, 1]);
+        let report = Report::from(counting_tables);
+        assert_eq!(report.flow(&Flow::Recovery), vec![&vec![0, 2, 4], &vec![0, 1]]);
+        assert_eq!(report.flow(&Flow::Waning), Vec::<&Vec<usize>>::new());
+        assert_eq!(report.flow_average(&Flow::Recovery).iter().map(|v| v.mean()).collect::<Vec<f64>>(), vec![0., 1.5, 4.]);
+        assert_eq!(report.flow_total(&Flow::Recovery), vec![6, 1]);
+    }
+
+    #[test]
+    fn overload_probability() {
+        let mut counting_tables: Vec<CountingTable> = vec![
+            Individual::iter().map(|i| (i, vec![0, 0])).collect(),
+            Individual::iter().map(|i| (i, vec![0, 0])).collect(),
+            Individual::iter().map(|i| (i, vec![0, 0])).collect(),
+            Individual::iter().map(|i| (i, vec![0, 0])).collect(),
+        ];
+        *counting_tables[0].overload_mut() = vec![0, 3];
+        *counting_tables[1].overload_mut() = vec![0, 0];
+        let report = Report::from(counting_tables);
+        assert_eq!(report.overload_probability(), 0.25);
+        assert_eq!(Report::default().overload_probability(), 0.);
+    }
+
+    #[test]
+    fn infections_by_building() {
+        let mut school = Attendance::new("School");
+        school.push(4, 1, 2).push(4, 3, 1);
+        let mut gym = Attendance::new("Gym");
+        gym.push(1, 0, 0);
+        let report = Report {
+            attendances: vec![vec![school.clone(), gym], vec![school]],
+            ..Default::default()
+        };
+        let infections: Vec<(String, f64)> = report.infections_by_building().into_iter()
+            .map(|(name, average)| (name, average.mean()))
+            .collect();
+        assert_eq!(infections, vec![("School".to_string(), 3.), ("Gym".to_string(), 0.)]);
+        assert_eq!(report.visitors_by_building()[0].1.mean(), 8.);
+        assert_eq!(report.infected_visitors_by_building()[0].1.mean(), 4.);
+    }
+
     #[test]
     fn healthy_initial() {
         let counting_tables: Vec<CountingTable> = vec![
             Individual::iter().map(|i| (i, vec![0, 0])).collect(),
             Individual::iter().map(|i| (i, vec![1, 2])).collect()
         ];
-        let report = Report { counting_tables };
+        let report = Report::from(counting_tables);
         assert_eq!(report.individual_first(&Individual::Healthy), 0);
     }
 }
\ No newline at end of file
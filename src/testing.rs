@@ -0,0 +1,90 @@
+use crate::Individual;
+use crate::probability::Probability;
+use getset::{Getters, Setters, MutGetters};
+use serde::{Serialize, Deserialize};
+
+/// Testing of individuals, where positives are placed into quarantine.
+///
+/// Each day, up to `capacity` random individuals that are not sick are tested.
+/// Quarantined individuals do not visit buildings, see `Board::test`.
+///
+/// # Examples
+///
+/// Ten tests per day, that detect nine out of ten infected individuals
+/// and that are wrong for one out of a hundred non-infected ones.
+/// ```
+/// # use core::convert::TryFrom;
+/// # use virus_alarm::{testing::Testing, probability::Probability};
+/// let testing = Testing {
+///     sensitivity: Probability::try_from(0.9).unwrap(),
+///     specificity: Probability::try_from(0.99).unwrap(),
+///     capacity: 10,
+///     quarantine_days: 5,
+/// };
+/// ```
+#[derive(Debug, Hash, Clone, PartialEq, Eq, Getters, Setters, MutGetters, Serialize, Deserialize)]
+pub struct Testing {
+    /// Probability that an infected individual tests positive
+    #[getset(get = "pub", set = "pub", get_mut = "pub")]
+    pub sensitivity: Probability,
+    /// Probability that a non-infected individual tests negative
+    #[getset(get = "pub", set = "pub", get_mut = "pub")]
+    pub specificity: Probability,
+    /// Maximum number of tests per day
+    #[getset(get = "pub", set = "pub", get_mut = "pub")]
+    pub capacity: usize,
+    /// Days that positive individuals stay in quarantine
+    ///
+    /// If zero, positives are not placed into quarantine.
+    #[getset(get = "pub", set = "pub", get_mut = "pub")]
+    pub quarantine_days: usize,
+}
+
+impl Testing {
+    /// Returns `true` if `individual` tests positive, drawing randomness from `rng`.
+    pub fn is_positive<R: ?Sized + rand::Rng>(&self, individual: &Individual, rng: &mut R) -> bool {
+        if individual.is_infected() {
+            self.sensitivity.sample(rng)
+        } else {
+            !self.specificity.sample(rng)
+        }
+    }
+}
+
+impl Default for Testing {
+    /// Perfect tests, but no capacity.
+    fn default() -> Self {
+        Testing {
+            sensitivity: Probability::ONE,
+            specificity: Probability::ONE,
+            capacity: 0,
+            quarantine_days: 0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::convert::TryFrom;
+    use test_case::test_case;
+
+    #[test_case(Individual::Healthy, false)]
+    #[test_case(Individual::Exposed, true)]
+    #[test_case(Individual::Asymptomatic, true)]
+    #[test_case(Individual::Immune, false)]
+    fn is_positive(individual: Individual, expected: bool) {
+        let testing = Testing::default();
+        assert_eq!(testing.is_positive(&individual, &mut crate::tests::rng(1)), expected);
+    }
+
+    #[test]
+    fn false_positives() {
+        let testing = Testing { specificity: Probability::try_from(0.8).unwrap(), ..Default::default() };
+        let mut rng = crate::tests::rng(1);
+        let positives: average::Variance = (0..1_000)
+            .map(|_| if testing.is_positive(&Individual::Healthy, &mut rng) { 1. } else { 0. })
+            .collect();
+        assert!((positives.mean() - 0.2).abs() < 4. * positives.error());
+    }
+}
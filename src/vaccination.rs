@@ -0,0 +1,76 @@
+use getset::{Getters, Setters, MutGetters};
+use serde::{Serialize, Deserialize};
+
+/// Individuals that can receive a dose of a vaccination campaign.
+#[derive(Debug, Hash, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, Default)]
+pub enum Eligibility {
+    /// Only healthy individuals who have not been infected yet, so no dose is wasted
+    ///
+    /// Individuals who recovered, or whose immunity waned, are healthy again 
+    /// but no longer eligible, see `Disease::recovery` and `Disease::immunity_days`.
+    /// Individuals that are not healthy at the start of the game count as infected, except immune ones.
+    #[default]
+    Healthy,
+    /// Anyone who is not sick, since infected individuals can not be told apart,
+    /// so doses given to infected or immune individuals are wasted
+    Everyone,
+}
+
+/// Vaccination campaign, where some doses are given each day from a given day on.
+///
+/// Doses are given at the start of each stage of the game to random eligible individuals,
+/// who become immune if they were healthy, see `Board::vaccinate`.
+///
+/// # Examples
+///
+/// Five doses per day, starting on the tenth day.
+/// ```
+/// # use virus_alarm::vaccination::Vaccination;
+/// let vaccination = Vaccination {
+///     doses_per_day: 5,
+///     start_day: 10,
+///     ..Default::default()
+/// };
+/// assert_eq!(vaccination.doses(9), 0);
+/// assert_eq!(vaccination.doses(10), 5);
+/// ```
+#[derive(Debug, Hash, Clone, PartialEq, Eq, Getters, Setters, MutGetters, Serialize, Deserialize, Default)]
+pub struct Vaccination {
+    /// Number of doses given each day
+    #[getset(get = "pub", set = "pub", get_mut = "pub")]
+    pub doses_per_day: usize,
+    /// First day in which doses are given
+    #[getset(get = "pub", set = "pub", get_mut = "pub")]
+    #[serde(default)]
+    pub start_day: usize,
+    /// Individuals that can receive a dose
+    #[getset(get = "pub", set = "pub", get_mut = "pub")]
+    #[serde(default)]
+    pub eligibility: Eligibility,
+}
+
+impl Vaccination {
+    /// Returns the number of doses available on `day`.
+    pub fn doses(&self, day: usize) -> usize {
+        if day >= self.start_day {
+            self.doses_per_day
+        } else {
+            0
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    #[test_case(0, 0)]
+    #[test_case(1, 0)]
+    #[test_case(2, 3)]
+    #[test_case(100, 3)]
+    fn doses(day: usize, expected: usize) {
+        let vaccination = Vaccination { doses_per_day: 3, start_day: 2, ..Default::default() };
+        assert_eq!(vaccination.doses(day), expected);
+    }
+}